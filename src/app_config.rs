@@ -0,0 +1,66 @@
+use embassy_time::Duration;
+
+use crate::{
+    shared_const::{
+        FAST_FLASH_DELAY, SLOW_FLASH_DELAY, SOFT_START_DURATION, SOS_ARM_WINDOW, WATCHDOG_TIMEOUT,
+    },
+    ButtonConfig, ModeTable,
+};
+
+/// The application's tunable settings, gathered in one place so that they can be chosen at boot
+/// rather than compiled in.
+///
+/// `AppConfig::default()` gives the `shared_const` values, i.e. today's behavior. `inner_main`
+/// creates the config once and hands its parts to whatever uses them: `button` to
+/// `Button::with_config`, the flash delays (through `LedState::execute`) to
+/// `Schedule::fast_no_delay_with` and friends, `modes` to `LedState::execute`, and
+/// `watchdog_timeout` to `Watchdog::start`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let config = AppConfig {
+///     fast_flash_delay: Duration::from_millis(100),
+///     idle_timeout: Some(Duration::from_secs(30)),
+///     ..AppConfig::default()
+/// };
+/// let mut button0 = Button::with_config(hardware.button0, config.button);
+/// ```
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct AppConfig {
+    /// Each "on" and "off" of the fast flashing states; `FAST_FLASH_DELAY` by default.
+    pub fast_flash_delay: Duration,
+    /// Each "on" and "off" of the slow flashing state; `SLOW_FLASH_DELAY` by default.
+    pub slow_flash_delay: Duration,
+    /// How both buttons debounce and classify presses.
+    pub button: ButtonConfig,
+    /// How long a state waits for a press before moving on by itself (see
+    /// `LedState::execute`), or `None` (the default) to wait forever.
+    pub idle_timeout: Option<Duration>,
+    /// How long `LedState::SosArmed` waits for the confirming press; `SOS_ARM_WINDOW` by
+    /// default.
+    pub sos_arm_window: Duration,
+    /// How long the LEDs take to ramp up to full brightness on entering `LedState::AlwaysOn`;
+    /// `SOFT_START_DURATION` by default. A zero duration snaps them on.
+    pub soft_start_duration: Duration,
+    /// What each state shows on the LEDs; the built-in modes by default.
+    pub modes: ModeTable,
+    /// How long the main loop may go unfed before the watchdog resets the device;
+    /// `WATCHDOG_TIMEOUT` by default.
+    pub watchdog_timeout: Duration,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            fast_flash_delay: FAST_FLASH_DELAY,
+            slow_flash_delay: SLOW_FLASH_DELAY,
+            button: ButtonConfig::default(),
+            idle_timeout: None,
+            sos_arm_window: SOS_ARM_WINDOW,
+            soft_start_duration: SOFT_START_DURATION,
+            modes: ModeTable::default(),
+            watchdog_timeout: WATCHDOG_TIMEOUT,
+        }
+    }
+}
@@ -1,55 +1,381 @@
-use defmt::info;
-use embassy_futures::select::{select, Either};
-use embassy_rp::gpio::Input;
-use embassy_time::Timer;
+use defmt::debug;
+use embassy_futures::{
+    join::join,
+    select::{select, select3, Either, Either3},
+};
+use embassy_rp::gpio::{DormantWake, Input, Level};
+use embassy_time::{Duration, Instant, Timer};
 
-use crate::shared_const::{BUTTON_DEBOUNCE_DELAY, LONG_PRESS_DURATION};
+use crate::{
+    error::{Error, Result},
+    shared_const::{
+        AUTOREPEAT_INTERVAL, BUTTON_DEBOUNCE_DELAY, CHORD_WINDOW, LONG_PRESS_DURATION,
+        MULTI_PRESS_WINDOW, ONE_DAY, VERY_LONG_PRESS_DURATION, ZERO_DELAY,
+    },
+    InputPin,
+};
 
-/// An abstract button backed by an Embassy input pin.
-pub struct Button<'a>(Input<'a>);
+/// An abstract button backed by an input pin, by default an Embassy GPIO `Input`.
+pub struct Button<P = Input<'static>> {
+    input: P,
+    config: ButtonConfig,
+    /// How many `PressEvent::Repeat`s the current hold has produced, or 0 if not autorepeating.
+    repeats: u32,
+}
+
+/// Timing thresholds a `Button` uses to debounce and classify presses.
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct ButtonConfig {
+    /// How long to wait after each edge for the contacts to settle.
+    pub debounce: Duration,
+    /// How long a press must be held to count as `PressDuration::Long`.
+    pub long_press: Duration,
+    /// How long a press must be held to count as `PressDuration::VeryLong`.
+    pub very_long_press: Duration,
+    /// How long the line must stay up after a release edge for a press to count as over, so
+    /// that a noise glitch mid-hold doesn't end it early; `BUTTON_DEBOUNCE_DELAY` by default.
+    /// Each release is reported this much later, and a release less than this before a
+    /// threshold counts as held past it. `ZERO_DELAY` takes the first release edge.
+    pub release_confirm: Duration,
+    /// Which input level means "pressed".
+    pub polarity: ButtonPolarity,
+}
+
+/// How a button is wired, i.e. which input level means "pressed".
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, defmt::Format)]
+pub enum ButtonPolarity {
+    /// The button pulls the line high when pressed (pull-down wiring, as on the reference
+    /// board).
+    #[default]
+    ActiveHigh,
+    /// The button pulls the line low when pressed (pull-up wiring).
+    ActiveLow,
+}
+
+impl Default for ButtonConfig {
+    fn default() -> Self {
+        Self {
+            debounce: BUTTON_DEBOUNCE_DELAY,
+            long_press: LONG_PRESS_DURATION,
+            very_long_press: VERY_LONG_PRESS_DURATION,
+            release_confirm: BUTTON_DEBOUNCE_DELAY,
+            polarity: ButtonPolarity::default(),
+        }
+    }
+}
+
+impl<P: InputPin> Button<P> {
+    /// Creates a new `Button` instance with the default `ButtonConfig`.
+    #[must_use]
+    pub fn new(button: P) -> Self {
+        Self::with_config(button, ButtonConfig::default())
+    }
 
-impl<'a> Button<'a> {
-    /// Creates a new `Button` instance.
+    /// Creates a new `Button` instance with custom timing, e.g. a longer debounce for a bouncy
+    /// switch.
     #[must_use]
-    pub const fn new(button: Input<'a>) -> Self {
-        Self(button)
+    pub const fn with_config(button: P, config: ButtonConfig) -> Self {
+        Self {
+            input: button,
+            config,
+            repeats: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_down(&self) -> bool {
+        match self.config.polarity {
+            ButtonPolarity::ActiveHigh => self.input.is_high(),
+            ButtonPolarity::ActiveLow => self.input.is_low(),
+        }
     }
 
     #[inline]
     async fn wait_for_button_up(&mut self) -> &mut Self {
-        self.0.wait_for_low().await;
+        match self.config.polarity {
+            ButtonPolarity::ActiveHigh => self.input.wait_for_low().await,
+            ButtonPolarity::ActiveLow => self.input.wait_for_high().await,
+        }
         self
     }
 
     #[inline]
     async fn wait_for_button_down(&mut self) -> &mut Self {
-        self.0.wait_for_high().await;
+        match self.config.polarity {
+            ButtonPolarity::ActiveHigh => self.input.wait_for_high().await,
+            ButtonPolarity::ActiveLow => self.input.wait_for_low().await,
+        }
+        self
+    }
+
+    /// Waits for a press to end, i.e. for a release edge after which the button is still up
+    /// `release_confirm` later, and returns when that edge came. A shorter glitch is ignored
+    /// and the hold goes on.
+    async fn wait_for_hold_end(&mut self) -> Instant {
+        let confirm = self.config.release_confirm;
+        loop {
+            self.wait_for_button_up().await;
+            let released_at = Instant::now();
+            if confirm == ZERO_DELAY {
+                return released_at;
+            }
+            Timer::after(confirm).await;
+            if !self.is_down() {
+                return released_at;
+            }
+            debug!("button: release glitch ignored");
+        }
+    }
+
+    /// Waits until the button is down and has settled, i.e. for the `ButtonConfig`'s `debounce`
+    /// after the press edge.
+    ///
+    /// If the button is already down, this only waits out the debounce. To wait for a fresh
+    /// press, call `wait_for_release()` first, as `press_duration` does.
+    pub async fn wait_for_press(&mut self) -> &mut Self {
+        self.wait_for_button_down().await;
+        Timer::after(self.config.debounce).await;
         self
     }
 
+    /// Waits until the button is up and has settled, i.e. for the `ButtonConfig`'s `debounce`
+    /// after the release edge.
+    ///
+    /// If the button is already up, this only waits out the debounce.
+    pub async fn wait_for_release(&mut self) -> &mut Self {
+        self.wait_for_button_up().await;
+        Timer::after(self.config.debounce).await;
+        self
+    }
+
+    /// Checks that the button isn't stuck down, e.g. shorted or jammed, as a self-test at boot.
+    ///
+    /// This reads the line once. If the button is up, that's the end of it. If it is down, this
+    /// waits up to `window` for it to come up (`BUTTON_STUCK_WINDOW`, one second, is a good
+    /// choice): long enough that a stray touch at power-on doesn't fail the check, and short
+    /// enough not to delay the boot much. Bouncing is harmless: any release within the window
+    /// passes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ButtonStuck` if the button is down for the whole `window`.
+    pub async fn check_stuck(&mut self, window: Duration) -> Result<()> {
+        if !self.is_down() {
+            return Ok(());
+        }
+        match select(self.wait_for_button_up(), Timer::after(window)).await {
+            Either::First(_) => Ok(()),
+            Either::Second(()) => Err(Error::ButtonStuck),
+        }
+    }
+
     /// Measures the duration of a button press.
     ///
     /// This method does not wait for the button to be released.  It only waits
-    /// as long as necessary to determine whether the press was "short" or "long".
+    /// as long as necessary to determine whether the press was "short", "long" or "very long".
+    /// A long press is therefore reported at release (or when it becomes very long), while a
+    /// very long press is reported as soon as it crosses the `ButtonConfig`'s `very_long_press`.
     pub async fn press_duration(&mut self) -> PressDuration {
-        self.wait_for_button_up().await;
-        Timer::after(BUTTON_DEBOUNCE_DELAY).await;
+        self.wait_for_release().await;
+        self.wait_for_press().await;
+        let press_duration = self.hold_duration(Instant::now()).await;
+        debug!("button: press duration {}", press_duration);
+        press_duration
+    }
+
+    /// Classifies a press that is already down and debounced, counting the thresholds from
+    /// `since`.
+    async fn hold_duration(&mut self, since: Instant) -> PressDuration {
+        let config = self.config;
+        let long_at = since.checked_add(config.long_press).unwrap_or(Instant::MAX);
+        match select(self.wait_for_hold_end(), Timer::at(long_at)).await {
+            Either::First(_) => PressDuration::Short,
+            Either::Second(()) => {
+                let very_long_at =
+                    since.checked_add(config.very_long_press).unwrap_or(Instant::MAX).max(long_at);
+                match select(self.wait_for_hold_end(), Timer::at(very_long_at)).await {
+                    Either::First(_) => PressDuration::Long,
+                    Either::Second(()) => PressDuration::VeryLong,
+                }
+            },
+        }
+    }
+
+    /// With this button just pressed and debounced, waits up to `CHORD_WINDOW` for `other` to
+    /// join it. Returns `None` for a chord, or else this button's press duration. A release
+    /// glitch shorter than `release_confirm` doesn't end the press (see `wait_for_hold_end`).
+    async fn chord_with<Q: InputPin>(&mut self, other: &mut Button<Q>) -> Option<PressDuration> {
+        let since = Instant::now();
+        let other_joined = match select3(
+            other.wait_for_press(),
+            self.wait_for_hold_end(),
+            Timer::after(CHORD_WINDOW),
+        )
+        .await
+        {
+            Either3::First(_) => true,
+            Either3::Second(_) => return Some(PressDuration::Short),
+            Either3::Third(()) => false,
+        };
+        if !other_joined {
+            return Some(self.hold_duration(since).await);
+        }
+        // `other` is down and settled; it is a chord only if this button still is too.
+        (!self.is_down()).then_some(PressDuration::Short)
+    }
+
+    /// Waits for a long (or very long) press, ignoring any short taps before it, e.g. so that a
+    /// stray tap can't end a critical state. Returns `PressDuration::Long` or
+    /// `PressDuration::VeryLong`, as soon as `press_duration` would.
+    ///
+    /// Each discarded tap is measured by `press_duration` in full, so its release is debounced
+    /// before the next press is looked for, and a bouncing tap counts as one. The `long_press`
+    /// threshold starts over with each press; holds don't add up across taps.
+    pub async fn wait_for_long_press(&mut self) -> PressDuration {
+        loop {
+            match self.press_duration().await {
+                PressDuration::Short => debug!("button: short tap ignored"),
+                press_duration => return press_duration,
+            }
+        }
+    }
+
+    /// Like `press_duration`, but gives up after `timeout`.
+    ///
+    /// Returns `None` if no press has been classified by the deadline, including a press that
+    /// started but was still too short to classify.
+    pub async fn press_duration_timeout(&mut self, timeout: Duration) -> Option<PressDuration> {
+        match select(self.press_duration(), Timer::after(timeout)).await {
+            Either::First(press_duration) => Some(press_duration),
+            Either::Second(()) => None,
+        }
+    }
+
+    /// Measures how long the button is held, from press to release.
+    ///
+    /// Unlike `press_duration`, this waits for the release. Presses longer than `ONE_DAY` are
+    /// reported as `ONE_DAY`.
+    pub async fn timed_press(&mut self) -> Duration {
+        let config = self.config;
+        self.wait_for_release().await;
         self.wait_for_button_down().await;
-        Timer::after(BUTTON_DEBOUNCE_DELAY).await;
-        let press_duration =
-            match select(self.wait_for_button_up(), Timer::after(LONG_PRESS_DURATION)).await {
-                Either::First(_) => PressDuration::Short,
-                Either::Second(()) => PressDuration::Long,
+        let pressed_at = Instant::now();
+        Timer::after(config.debounce).await;
+        let timed_press = match select(self.wait_for_hold_end(), Timer::after(ONE_DAY)).await {
+            Either::First(released_at) => {
+                released_at.checked_duration_since(pressed_at).unwrap_or(ZERO_DELAY).min(ONE_DAY)
+            },
+            Either::Second(()) => ONE_DAY,
+        };
+        debug!("button: timed press {} ms", timed_press.as_millis());
+        timed_press
+    }
+
+    /// Measures a group of taps, or a long press.
+    ///
+    /// Taps that each start within `MULTI_PRESS_WINDOW` of the previous tap's release are
+    /// grouped, up to three. A long press wins over counting: if any press in the group is
+    /// held for the `ButtonConfig`'s `long_press`, the result is `PressPattern::Long`, reported
+    /// without waiting for release.
+    pub async fn press_pattern(&mut self) -> PressPattern {
+        let config = self.config;
+        self.wait_for_release().await;
+        self.wait_for_press().await;
+
+        let mut taps: u8 = 0;
+        let press_pattern = loop {
+            // Wait for this press to end, or to become a long press.
+            match select(self.wait_for_hold_end(), Timer::after(config.long_press)).await {
+                Either::First(_) => {},
+                Either::Second(()) => break PressPattern::Long,
+            }
+            taps = taps.saturating_add(1);
+            if taps >= 3 {
+                break PressPattern::Triple;
+            }
+            Timer::after(config.debounce).await;
+
+            // Wait for another tap to join the group.
+            match select(self.wait_for_button_down(), Timer::after(MULTI_PRESS_WINDOW)).await {
+                Either::First(_) => {},
+                Either::Second(()) if taps == 1 => break PressPattern::Single,
+                Either::Second(()) => break PressPattern::Double,
+            }
+            Timer::after(config.debounce).await;
+        };
+        debug!("button: press pattern {}", press_pattern);
+        press_pattern
+    }
+
+    /// Waits for a tap, or for the next repeat while the button is held.
+    ///
+    /// A press released before the `ButtonConfig`'s `long_press` is `PressEvent::Short`. A press
+    /// held that long becomes `PressEvent::Repeat(1)` right then, and as long as it stays down,
+    /// the following calls return `Repeat(2)`, `Repeat(3)` and so on, one every
+    /// `AUTOREPEAT_INTERVAL`. So a caller that loops on this can step once per tap and scrub
+    /// quickly while the button is held.
+    ///
+    /// Between repeats, the button's release edge is raced against the interval's timer, so a
+    /// release is noticed at once rather than at the next tick. It ends the run without an event
+    /// of its own: after the debounce, the call goes on to wait for the next press. Call this
+    /// again promptly after a `Repeat`, as the run only continues while the call is waiting.
+    pub async fn press_with_autorepeat(&mut self) -> PressEvent {
+        let config = self.config;
+        if self.repeats > 0 {
+            match select(self.wait_for_hold_end(), Timer::after(AUTOREPEAT_INTERVAL)).await {
+                Either::First(_) => {
+                    self.repeats = 0;
+                    Timer::after(config.debounce).await;
+                },
+                Either::Second(()) => {
+                    self.repeats = self.repeats.saturating_add(1);
+                    return PressEvent::Repeat(self.repeats);
+                },
+            }
+        }
+
+        self.wait_for_release().await;
+        self.wait_for_press().await;
+        let press_event =
+            match select(self.wait_for_hold_end(), Timer::after(config.long_press)).await {
+                Either::First(_) => PressEvent::Short,
+                Either::Second(()) => {
+                    self.repeats = 1;
+                    PressEvent::Repeat(1)
+                },
             };
-        info!("Press duration: {:?}", press_duration);
-        press_duration
+        debug!("button: press event {}", press_event);
+        press_event
     }
 
-    /// Waits for the button to be pressed.
-    #[inline]
-    pub async fn wait_for_press(&mut self) -> &mut Self {
-        self.0.wait_for_rising_edge().await;
-        self
+    /// Waits for the button to change state and reports which way it went.
+    ///
+    /// If the button is up, this waits for `ButtonEvent::Down`; if it is down, for
+    /// `ButtonEvent::Up`. After the edge it waits out the `ButtonConfig`'s `debounce`, so bounces
+    /// within that window are coalesced into the one event. This makes it easy to combine button
+    /// input with other futures via `select`.
+    pub async fn next_event(&mut self) -> ButtonEvent {
+        let config = self.config;
+        let button_event = if self.is_down() {
+            self.wait_for_button_up().await;
+            ButtonEvent::Up
+        } else {
+            self.wait_for_button_down().await;
+            ButtonEvent::Down
+        };
+        Timer::after(config.debounce).await;
+        debug!("button: edge {}", button_event);
+        button_event
+    }
+
+    /// Arms the button to wake the RP2040 from `DORMANT` when it is next pressed, for as long as
+    /// the returned guard lives. Returns `None` if the button's pin can't wake the chip.
+    pub fn dormant_wake(&mut self) -> Option<DormantWake<'_>> {
+        let level = match self.config.polarity {
+            ButtonPolarity::ActiveHigh => Level::High,
+            ButtonPolarity::ActiveLow => Level::Low,
+        };
+        self.input.dormant_wake(level)
     }
 }
 
@@ -60,4 +386,101 @@ impl<'a> Button<'a> {
 pub enum PressDuration {
     Short,
     Long,
+    VeryLong,
+}
+
+impl PressDuration {
+    /// Classifies a press that lasted `duration`: `Long` if it lasted at least `threshold`
+    /// (inclusive, so a press of exactly `threshold` is long) and `Short` otherwise.
+    ///
+    /// This is the measured-duration counterpart of `Button::press_duration`, which decides
+    /// while the button is still down, for presses timed some other way. It never returns
+    /// `VeryLong`; compare with `ButtonConfig::very_long_press` for that.
+    #[must_use]
+    pub const fn from_duration(duration: Duration, threshold: Duration) -> Self {
+        if duration.as_ticks() >= threshold.as_ticks() {
+            Self::Long
+        } else {
+            Self::Short
+        }
+    }
+}
+
+// Check `PressDuration::from_duration` at its boundary: exactly the threshold is long, one
+// tick less is short.
+const _: () = {
+    let threshold = LONG_PRESS_DURATION;
+    let just_short = Duration::from_ticks(threshold.as_ticks().saturating_sub(1));
+    assert!(matches!(PressDuration::from_duration(threshold, threshold), PressDuration::Long));
+    assert!(matches!(PressDuration::from_duration(just_short, threshold), PressDuration::Short));
+    assert!(matches!(PressDuration::from_duration(ZERO_DELAY, ZERO_DELAY), PressDuration::Long));
+};
+
+/// A tap or an autorepeat, as reported by `Button::press_with_autorepeat`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, defmt::Format)]
+pub enum PressEvent {
+    /// The button was pressed and released before the hold threshold.
+    Short,
+    /// The button is being held. Counts from 1, at the hold threshold, up by one per
+    /// `AUTOREPEAT_INTERVAL`.
+    Repeat(u32),
+}
+
+/// A press on one of two buttons, or both pressed together, as measured by `press_or_chord`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, defmt::Format)]
+pub enum PairPress {
+    /// The first button was pressed on its own, for this long.
+    First(PressDuration),
+    /// The second button was pressed on its own, for this long.
+    Second(PressDuration),
+    /// Both buttons were pressed together.
+    Chord,
+}
+
+/// Waits for a press on either of two buttons, or for a chord: both pressed together.
+///
+/// Whichever button goes down first owns the press. If the other one follows within
+/// `CHORD_WINDOW` (both debounced, and the first still down once the second has settled), the
+/// result is `PairPress::Chord`, reported without waiting for release. A first button released
+/// before the other follows is a short press of its own, so tapping one and then the other is
+/// never a chord. Otherwise the first button's press is classified as by
+/// `Button::press_duration`, with its thresholds counted from when it settled, and the other
+/// button is ignored until the next call. Like `press_duration`, this first waits for both
+/// buttons to be up.
+pub async fn press_or_chord<P0: InputPin, P1: InputPin>(
+    first: &mut Button<P0>,
+    second: &mut Button<P1>,
+) -> PairPress {
+    join(first.wait_for_release(), second.wait_for_release()).await;
+    let first_down =
+        matches!(select(first.wait_for_press(), second.wait_for_press()).await, Either::First(_));
+    let pair_press = if first_down {
+        first.chord_with(second).await.map_or(PairPress::Chord, PairPress::First)
+    } else {
+        second.chord_with(first).await.map_or(PairPress::Chord, PairPress::Second)
+    };
+    debug!("button: pair press {}", pair_press);
+    pair_press
+}
+
+/// A debounced edge, as reported by `Button::next_event`.
+///
+/// These are one button's own edges. A chord involves both buttons, so it is reported by
+/// `press_or_chord`, as `PairPress::Chord`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, defmt::Format)]
+pub enum ButtonEvent {
+    /// The button was pressed.
+    Down,
+    /// The button was released.
+    Up,
+}
+
+/// A group of taps, or a long press, as measured by `Button::press_pattern`.
+#[expect(missing_docs, reason = "We don't need to document the variants of this enum.")]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, defmt::Format)]
+pub enum PressPattern {
+    Single,
+    Double,
+    Triple,
+    Long,
 }
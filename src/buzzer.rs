@@ -0,0 +1,82 @@
+use core::num::NonZeroU32;
+
+use embassy_rp::{
+    gpio::Level,
+    pwm::{Config, Pwm},
+};
+use embassy_time::Timer;
+
+use crate::{
+    error::Result,
+    shared_const::{BUZZER_CLOCK_DIVIDER, BUZZER_PWM_TOP},
+    Schedule,
+};
+
+/// A passive buzzer driven by a PWM output, so that patterns can be heard as well as seen.
+///
+/// While "on", the output is a `BUZZER_TONE_HZ` square wave; while "off", it is held low.
+pub struct Buzzer {
+    pwm: Pwm<'static>,
+    config: Config,
+}
+
+impl Buzzer {
+    /// Creates a new, silent `Buzzer` on `pwm`, which should have a single output (e.g. from
+    /// `Pwm::new_output_b`).
+    #[must_use]
+    pub fn new(mut pwm: Pwm<'static>) -> Self {
+        let mut config = Config::default();
+        config.divider = BUZZER_CLOCK_DIVIDER.into();
+        config.top = BUZZER_PWM_TOP;
+        pwm.set_config(&config);
+        Self { pwm, config }
+    }
+
+    /// Sounds the tone (`Level::High`) or silences it (`Level::Low`).
+    pub fn set_level(&mut self, level: Level) {
+        // A 50% duty cycle is the loudest square wave. Whichever of the slice's channels the
+        // buzzer is on, the other has no pin, so both are set.
+        let compare = if level == Level::High {
+            BUZZER_PWM_TOP.saturating_add(1).checked_div(2).unwrap_or(0)
+        } else {
+            0
+        };
+        self.config.compare_a = compare;
+        self.config.compare_b = compare;
+        self.pwm.set_config(&self.config);
+    }
+
+    /// Sounds `schedule`, with the tone on wherever an `Led` would be lit, then falls silent.
+    ///
+    /// As with an `Led`, a schedule without `repeat` never returns, so race it against whatever
+    /// should end it. A future dropped mid-tone leaves the tone on; call `set_level(Level::Low)`
+    /// afterwards.
+    pub async fn play<const N: usize>(&mut self, schedule: &Schedule<N>) {
+        self.set_level(Level::Low);
+        Timer::after(schedule.initial_delay).await;
+        if !schedule.on_off_durations.is_empty() {
+            let repeat = schedule.repeat.map(NonZeroU32::get);
+            let mut completed: u32 = 0;
+            while repeat.is_none_or(|cycles| completed < cycles) {
+                for (level, duration) in schedule.steps() {
+                    self.set_level(level);
+                    Timer::after(duration).await;
+                }
+                completed = completed.saturating_add(1);
+            }
+        }
+        self.set_level(Level::Low);
+    }
+
+    /// Sounds `text` in Morse code once, with the same timing as `Schedule::from_morse`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `Schedule::from_morse` can't encode `text`.
+    pub async fn play_morse(&mut self, text: &str, millis_per_dot: u64) -> Result<()> {
+        let mut schedule: Schedule = Schedule::from_morse(text, millis_per_dot)?;
+        schedule.repeat = Some(NonZeroU32::MIN);
+        self.play(&schedule).await;
+        Ok(())
+    }
+}
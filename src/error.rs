@@ -11,15 +11,73 @@ pub enum Error {
     // only recently moved from `std` (which is not available in bare-metal development) to `core`
     // (which is). Perhaps a future update of `embassy_executor::SpawnError` will implement
     // `core::error::Error` which will make this unnecessary.
-    #[display("{_0:?}")]
+    #[display("Failed to spawn a task: {_0:?}")]
     TaskSpawn(#[error(not(source))] embassy_executor::SpawnError),
 
+    #[display("Flash error: {_0:?}")]
+    Flash(#[error(not(source))] embassy_rp::flash::Error),
+
+    #[display("ADC error: {_0:?}")]
+    Adc(#[error(not(source))] embassy_rp::adc::Error),
+
+    #[cfg(feature = "serde")]
+    #[display("Postcard error: {_0}")]
+    Postcard(#[error(not(source))] postcard::Error),
+
     #[display("Failed to create schedule from slice: capacity exceeded")]
     ScheduleCapacityExceeded,
 
     #[display("Schedule cycle length must be even")]
     ScheduleCycleLengthMustBeEven,
 
+    #[display("Only the last duration of a schedule may be zero")]
+    ScheduleZeroDuration,
+
+    #[display("Schedule segments must alternate on and off, starting opposite the start level")]
+    ScheduleSegmentOutOfOrder,
+
     #[display("Arithmetic overflow")]
     ArithmeticOverflow,
+
+    #[display("Schedule scale denominator must not be zero")]
+    ScheduleScaleDivideByZero,
+
+    #[display("Schedule ramp minimum must not exceed its maximum")]
+    ScheduleRampMinExceedsMax,
+
+    #[display("Duty cycle must be at most 100 percent")]
+    InvalidDutyCycle,
+
+    #[display("Character {_0:?} has no Morse code representation")]
+    UnsupportedMorseChar(#[error(not(source))] char),
+
+    #[display("Morse message is too long")]
+    MorseMessageTooLong,
+
+    #[display("Schedule does not decode unambiguously as Morse code")]
+    MorseDecodeAmbiguous,
+
+    #[display("Button held down for the whole self-check")]
+    ButtonStuck,
+
+    #[display("Malformed serial console command")]
+    CommandParse,
+
+    #[display("The hardware was already initialized")]
+    HardwareAlreadyInitialized,
+
+    #[display("GPIO {pin} is assigned to more than one role")]
+    #[from(ignore)]
+    PinConflict { pin: u8 },
+}
+
+/// Logs the error with the same text as its `Display` implementation.
+#[expect(
+    clippy::missing_trait_methods,
+    reason = "The remaining methods are internal to defmt and have correct defaults."
+)]
+impl defmt::Format for Error {
+    fn format(&self, formatter: defmt::Formatter<'_>) {
+        defmt::write!(formatter, "{}", defmt::Display2Format(self));
+    }
 }
@@ -0,0 +1,53 @@
+//! A flight recorder of recent state transitions (with the `event-log` feature).
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::Instant;
+use heapless::HistoryBuffer;
+
+use crate::{shared_const::EVENT_LOG_CAPACITY, LedState, PairPress};
+
+/// The last `EVENT_LOG_CAPACITY` transitions, oldest first when read in order.
+static EVENT_LOG: Mutex<
+    CriticalSectionRawMutex,
+    RefCell<HistoryBuffer<Event, EVENT_LOG_CAPACITY>>,
+> = Mutex::new(RefCell::new(HistoryBuffer::new()));
+
+/// One transition of the state machine.
+#[derive(Clone, Copy, Debug, defmt::Format)]
+struct Event {
+    /// When the press was classified, in milliseconds since boot.
+    at_millis: u64,
+    from: LedState,
+    /// The press, or `None` if the idle timeout passed without one.
+    press: Option<PairPress>,
+    to: LedState,
+}
+
+/// Records that `press` (or the idle timeout, if `None`) moved the state machine `from` one
+/// state `to` another, dropping the oldest record once `EVENT_LOG_CAPACITY` are kept.
+pub fn record(from: LedState, press: Option<PairPress>, to: LedState) {
+    let event = Event {
+        at_millis: Instant::now().as_millis(),
+        from,
+        press,
+        to,
+    };
+    EVENT_LOG.lock(|log| log.borrow_mut().write(event));
+}
+
+/// Logs the recorded transitions over defmt, oldest first, to show how the device reached its
+/// current state.
+///
+/// `LedState::execute` calls this on every very long press. The records are kept, so a later
+/// dump repeats them.
+pub fn dump_log() {
+    EVENT_LOG.lock(|cell| {
+        let log = cell.borrow();
+        defmt::info!("event-log: last {} transitions", log.len());
+        for event in log.oldest_ordered() {
+            defmt::info!("event-log: {}", event);
+        }
+    });
+}
@@ -0,0 +1,80 @@
+//! A panic handler that blinks SOS on the on-board LED, for units with no debugger attached.
+//!
+//! Enable it with the `fault-blink` feature, which replaces `panic-probe`.
+//!
+//! By the time a panic handler runs, the code that panicked may have been in the middle of
+//! anything: polling a future, holding a critical section, or inside the executor itself. None of
+//! the async runtime can be trusted, and none of it can make progress anyway, since the handler
+//! never returns to the executor. So this handler awaits nothing: it masks interrupts, drives the
+//! LED's GPIO through the SIO registers directly (the `Output` that owns the pin may be mid-use),
+//! and times the blinks by counting CPU cycles instead of using `embassy_time`.
+
+use core::panic::PanicInfo;
+
+use embassy_rp::pac::{self, io::vals::Gpio0ctrlFuncsel};
+
+use crate::shared_const::{
+    morse_for, MorseSymbol, FAULT_BLINK_REPEATS, FAULT_BLINK_UNIT_MILLIS, FAULT_LED_PIN,
+    SYSTEM_CLOCK_CYCLES_PER_MILLI,
+};
+
+#[panic_handler]
+fn panic(_info: &PanicInfo<'_>) -> ! {
+    cortex_m::interrupt::disable();
+    // A watchdog reset would cut the code short and then hide it, so the unit stays halted until
+    // someone power-cycles it.
+    pac::WATCHDOG.ctrl().modify(|ctrl| ctrl.set_enable(false));
+    take_led_pin();
+
+    for _ in 0..FAULT_BLINK_REPEATS {
+        for letter in "SOS".chars() {
+            for &symbol in morse_for(letter).unwrap_or_default() {
+                set_led(true);
+                wait_units(match symbol {
+                    MorseSymbol::Dot => 1,
+                    MorseSymbol::Dash => 3,
+                });
+                set_led(false);
+                wait_units(1);
+            }
+            // With the gap after the last symbol, a letter gap of 3 units.
+            wait_units(2);
+        }
+        // With the letter gap, a word gap of 7 units.
+        wait_units(4);
+    }
+
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
+
+/// Hands `FAULT_LED_PIN` to the SIO as an output, whatever it was doing before.
+fn take_led_pin() {
+    pac::PADS_BANK0.gpio(FAULT_LED_PIN).modify(|pad| pad.set_od(false));
+    pac::IO_BANK0
+        .gpio(FAULT_LED_PIN)
+        .ctrl()
+        .write(|ctrl| ctrl.set_funcsel(Gpio0ctrlFuncsel::SIO_0.into()));
+    pac::SIO.gpio_oe(0).value_set().write_value(led_mask());
+}
+
+fn set_led(on: bool) {
+    let out = pac::SIO.gpio_out(0);
+    if on {
+        out.value_set().write_value(led_mask());
+    } else {
+        out.value_clr().write_value(led_mask());
+    }
+}
+
+const fn led_mask() -> u32 {
+    1 << FAULT_LED_PIN
+}
+
+/// Busy-waits for `units` Morse units of `FAULT_BLINK_UNIT_MILLIS` each.
+fn wait_units(units: u32) {
+    cortex_m::asm::delay(
+        units.saturating_mul(FAULT_BLINK_UNIT_MILLIS).saturating_mul(SYSTEM_CLOCK_CYCLES_PER_MILLI),
+    );
+}
@@ -0,0 +1,191 @@
+use core::{cell::RefCell, future::Future};
+
+use embassy_rp::gpio::{DormantWake, DormantWakeConfig, Flex, Input, Level};
+use embedded_hal::digital::{self, OutputPin, PinState};
+use embedded_hal_async::digital::Wait;
+
+/// The input-pin operations a `Button` needs, so that a `Button` can be driven by something other
+/// than an RP2040 GPIO, such as a `MockPin` (with the `mock` feature).
+///
+/// Besides `Input`, it is implemented for `Flex`, which can change its pull or turn into an
+/// output at runtime. A `Flex` should be set as an input before a `Button` reads it.
+pub trait InputPin {
+    /// Returns whether the pin is high.
+    fn is_high(&self) -> bool;
+    /// Returns whether the pin is low.
+    fn is_low(&self) -> bool;
+    /// Waits until the pin is high, returning at once if it already is.
+    fn wait_for_high(&mut self) -> impl Future<Output = ()>;
+    /// Waits until the pin is low, returning at once if it already is.
+    fn wait_for_low(&mut self) -> impl Future<Output = ()>;
+    /// Waits for the pin to next go from low to high.
+    fn wait_for_rising_edge(&mut self) -> impl Future<Output = ()>;
+    /// Waits for the pin to next go from high to low.
+    fn wait_for_falling_edge(&mut self) -> impl Future<Output = ()>;
+    /// Arms the pin to wake the RP2040 from `DORMANT` when it next goes to `level`, for as long
+    /// as the returned guard lives. Returns `None` if the pin can't wake the chip.
+    fn dormant_wake(&mut self, level: Level) -> Option<DormantWake<'_>>;
+}
+
+impl InputPin for Input<'_> {
+    fn is_high(&self) -> bool {
+        Input::is_high(self)
+    }
+
+    fn is_low(&self) -> bool {
+        Input::is_low(self)
+    }
+
+    async fn wait_for_high(&mut self) {
+        Input::wait_for_high(self).await;
+    }
+
+    async fn wait_for_low(&mut self) {
+        Input::wait_for_low(self).await;
+    }
+
+    async fn wait_for_rising_edge(&mut self) {
+        Input::wait_for_rising_edge(self).await;
+    }
+
+    async fn wait_for_falling_edge(&mut self) {
+        Input::wait_for_falling_edge(self).await;
+    }
+
+    fn dormant_wake(&mut self, level: Level) -> Option<DormantWake<'_>> {
+        Some(Input::dormant_wake(self, edge_wake(level)))
+    }
+}
+
+impl InputPin for Flex<'_> {
+    fn is_high(&self) -> bool {
+        Flex::is_high(self)
+    }
+
+    fn is_low(&self) -> bool {
+        Flex::is_low(self)
+    }
+
+    async fn wait_for_high(&mut self) {
+        Flex::wait_for_high(self).await;
+    }
+
+    async fn wait_for_low(&mut self) {
+        Flex::wait_for_low(self).await;
+    }
+
+    async fn wait_for_rising_edge(&mut self) {
+        Flex::wait_for_rising_edge(self).await;
+    }
+
+    async fn wait_for_falling_edge(&mut self) {
+        Flex::wait_for_falling_edge(self).await;
+    }
+
+    fn dormant_wake(&mut self, level: Level) -> Option<DormantWake<'_>> {
+        Some(Flex::dormant_wake(self, edge_wake(level)))
+    }
+}
+
+/// An `InputPin` for any pin with the `embedded-hal` traits, so that a `Button` can run on an MCU
+/// other than the RP2040.
+///
+/// `embedded-hal` reads take `&mut self`, so the pin is kept in a `RefCell`. A read that fails
+/// counts as neither high nor low, i.e. as the button being up. A wait that fails logs a warning
+/// and never returns, rather than spinning on the error. Such a pin can't wake the chip from
+/// `DORMANT`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut button = Button::new(HalInput::new(board.user_button));
+/// let press_duration = button.press_duration().await;
+/// ```
+pub struct HalInput<P> {
+    pin: RefCell<P>,
+}
+
+impl<P> HalInput<P> {
+    /// Wraps `pin`.
+    #[must_use]
+    pub const fn new(pin: P) -> Self {
+        Self {
+            pin: RefCell::new(pin),
+        }
+    }
+}
+
+impl<P: digital::InputPin + Wait> InputPin for HalInput<P> {
+    fn is_high(&self) -> bool {
+        self.pin.borrow_mut().is_high().unwrap_or(false)
+    }
+
+    fn is_low(&self) -> bool {
+        self.pin.borrow_mut().is_low().unwrap_or(false)
+    }
+
+    async fn wait_for_high(&mut self) {
+        wait_or_pend(self.pin.get_mut().wait_for_high().await).await;
+    }
+
+    async fn wait_for_low(&mut self) {
+        wait_or_pend(self.pin.get_mut().wait_for_low().await).await;
+    }
+
+    async fn wait_for_rising_edge(&mut self) {
+        wait_or_pend(self.pin.get_mut().wait_for_rising_edge().await).await;
+    }
+
+    async fn wait_for_falling_edge(&mut self) {
+        wait_or_pend(self.pin.get_mut().wait_for_falling_edge().await).await;
+    }
+
+    fn dormant_wake(&mut self, _level: Level) -> Option<DormantWake<'_>> {
+        None
+    }
+}
+
+/// Returns at once if `result` is `Ok`, and otherwise warns and waits forever.
+async fn wait_or_pend<E>(result: Result<(), E>) {
+    if result.is_err() {
+        defmt::warn!("button: pin wait failed");
+        core::future::pending::<()>().await;
+    }
+}
+
+/// The output operations `device_loop` needs, so that an `Led` can drive a pin other than an
+/// RP2040 GPIO or PWM channel.
+///
+/// It is implemented for `LedPin`, the RP2040 default, and for every `embedded-hal`
+/// `OutputPin`, which is on at any nonzero brightness (and logs a warning if a write fails).
+pub trait LedOutput {
+    /// Lights the LED at `brightness` percent, where 0 is off. If `invert`, the pin is low (or
+    /// its duty cycle is `100 - brightness`) where it would otherwise be high.
+    fn set(&mut self, brightness: u8, invert: bool);
+    /// Returns whether the pin is only ever fully on or off, so that a fade has to approximate
+    /// each brightness by switching (see `Led::fade_to`).
+    fn is_digital(&self) -> bool;
+}
+
+impl<P: OutputPin> LedOutput for P {
+    fn set(&mut self, brightness: u8, invert: bool) {
+        if self.set_state(PinState::from((brightness > 0) != invert)).is_err() {
+            defmt::warn!("led: pin write failed");
+        }
+    }
+
+    fn is_digital(&self) -> bool {
+        true
+    }
+}
+
+/// Wakes from `DORMANT` on the edge to `level`. (A level wake would re-fire at once if the
+/// button were still held.)
+const fn edge_wake(level: Level) -> DormantWakeConfig {
+    DormantWakeConfig {
+        edge_high: matches!(level, Level::High),
+        edge_low: matches!(level, Level::Low),
+        level_high: false,
+        level_low: false,
+    }
+}
@@ -1,35 +1,294 @@
+use core::cell::Cell;
+
 use embassy_rp::{
-    gpio::{self, Level},
-    peripherals::CORE1,
+    adc::{self, Adc, AdcPin},
+    bind_interrupts,
+    flash::Flash,
+    gpio::{self, AnyPin, Pin as _},
+    peripherals::{ADC, CORE1, FLASH, PIN_2, PIN_26, PIN_27, PIN_3, PWM_SLICE1, USB, WATCHDOG},
+    pwm::{self, ChannelAPin, ChannelBPin, Pwm},
+    usb::{self, Driver},
     Peripherals,
 };
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    mutex,
+};
+
+use crate::{
+    error::{Error, Result},
+    pwm_slice::{PwmChannel, PwmSlice},
+    state_store::StateFlash,
+    Buzzer, LightSensor, Potentiometer, SharedAdc, Watchdog,
+};
+
+bind_interrupts!(struct Irqs {
+    ADC_IRQ_FIFO => adc::InterruptHandler;
+    USBCTRL_IRQ => usb::InterruptHandler<USB>;
+});
+
+/// The PWM slice that drives `led0` and `led1`.
+static LED_PWM_SLICE: PwmSlice = PwmSlice::new();
+
+/// Whether `Hardware::take` has already called `embassy_rp::init`. (The RP2040 has no atomic
+/// swap, so this is a `Cell` behind a critical section rather than an `AtomicBool`.)
+static TAKEN: Mutex<CriticalSectionRawMutex, Cell<bool>> = Mutex::new(Cell::new(false));
 
 /// Represents the hardware components of the clock.
+///
+/// The fields are public so that each can be moved out to whatever drives it. Once moved, a
+/// field belongs to its new owner for good: in particular, `Led::new` hands its pin to a task
+/// that runs forever, so an LED's pin can't be reclaimed. `Hardware` keeps nothing back, so it
+/// can't be turned back into `Peripherals` either.
+///
+/// A pin that must change at runtime (e.g. between input and output) should stay out of
+/// `Hardware`: take it from `Peripherals` before building the `HardwareConfig` by hand, and keep
+/// it as a `gpio::Flex`, which can change its pull and direction and still drive a `Button`.
+/// The buttons' pull is chosen up front with `HardwareConfig::button_pull`.
 pub struct Hardware<'a> {
-    /// An LED
-    pub led0: gpio::Output<'a>,
-    /// Another LED
-    pub led1: gpio::Output<'a>,
-    /// The button that controls the clock.
-    pub button: gpio::Input<'a>,
-    /// The second core of the RP2040 (not currently used).
+    /// An LED, driven by PWM so that it can be dimmed.
+    pub led0: PwmChannel,
+    /// Another LED, on the other channel of the same PWM slice.
+    pub led1: PwmChannel,
+    /// The Pico's on-board LED (GPIO 25 by default), used as a heartbeat.
+    pub led_onboard: gpio::Output<'static>,
+    /// The button that moves to the next state (GPIO 13 by default).
+    pub button0: gpio::Input<'a>,
+    /// The button that moves to the previous state (GPIO 14 by default).
+    pub button1: gpio::Input<'a>,
+    /// A passive buzzer that sounds the SOS (GPIO 15 by default).
+    pub buzzer: Buzzer,
+    /// A knob that sets how fast the LEDs blink (GPIO 26 by default).
+    pub potentiometer: Potentiometer,
+    /// A photoresistor that dims the LEDs in the dark (GPIO 27 by default).
+    pub light_sensor: LightSensor,
+    /// The USB port, used for the serial console.
+    pub usb: Driver<'static, USB>,
+    /// The on-board flash, used to persist the `LedState`.
+    pub flash: StateFlash<'a>,
+    /// The second core of the RP2040, which can run an `Led` via `Led::new_on_core1`.
     pub core1: CORE1,
+    /// The watchdog, not yet started.
+    pub watchdog: Watchdog,
+}
+
+impl Hardware<'_> {
+    /// Creates the hardware from the peripherals in `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PinConflict` if `config` assigns the same GPIO to more than one of the
+    /// LEDs, buttons, potentiometer and light sensor. (The buzzer's pin is hidden inside its
+    /// `Pwm`, so it isn't checked.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if called a second time, as the shared ADC can only be created once.
+    pub fn new<Slice, Pin0, Pin1, KnobPin, LightPin>(
+        config: HardwareConfig<Slice, Pin0, Pin1, KnobPin, LightPin>,
+    ) -> Result<Self>
+    where
+        Slice: pwm::Slice,
+        Pin0: ChannelAPin<Slice>,
+        Pin1: ChannelBPin<Slice>,
+        KnobPin: AdcPin,
+        LightPin: AdcPin,
+    {
+        // Distinct peripherals are normally distinct pins, but `AnyPin`s (and stolen
+        // peripherals) can alias, which would silently wire two roles to one pin.
+        let pins = [
+            config.led0.pin(),
+            config.led1.pin(),
+            config.led_onboard.pin(),
+            config.button0.pin(),
+            config.button1.pin(),
+            config.potentiometer.pin(),
+            config.light_sensor.pin(),
+        ];
+        if let Some(pin) = first_repeat(&pins) {
+            return Err(Error::PinConflict { pin });
+        }
+
+        let pwm =
+            Pwm::new_output_ab(config.led_slice, config.led0, config.led1, pwm::Config::default());
+        let (led0, led1) = LED_PWM_SLICE.init(pwm);
+        let led_onboard = gpio::Output::new(config.led_onboard, gpio::Level::Low);
+        let button0 = gpio::Input::new(config.button0, config.button_pull);
+        let button1 = gpio::Input::new(config.button1, config.button_pull);
+        let buzzer = Buzzer::new(config.buzzer);
+        // The ADC is kept for as long as the firmware runs, i.e. forever. `ADC` can only be
+        // taken once, so neither can it.
+        let driver = Adc::new(config.adc, Irqs, adc::Config::default());
+        let Some(shared) = cortex_m::singleton!(: SharedAdc = mutex::Mutex::new(driver)) else {
+            defmt::panic!("The shared ADC was already created");
+        };
+        let adc: &'static SharedAdc = shared;
+        let potentiometer =
+            Potentiometer::new(adc, adc::Channel::new_pin(config.potentiometer, gpio::Pull::None));
+        let light_sensor =
+            LightSensor::new(adc, adc::Channel::new_pin(config.light_sensor, gpio::Pull::None));
+        let usb = Driver::new(config.usb, Irqs);
+        let flash = Flash::new_blocking(config.flash);
+
+        Ok(Self {
+            led0,
+            led1,
+            led_onboard,
+            button0,
+            button1,
+            buzzer,
+            potentiometer,
+            light_sensor,
+            usb,
+            flash,
+            core1: config.core1,
+            watchdog: Watchdog::new(config.watchdog),
+        })
+    }
+
+    /// Initializes the chip with `embassy_rp::init` and creates the hardware with the default
+    /// assignment (see `HardwareConfig::new`), like `Peripherals::take` in other HALs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::HardwareAlreadyInitialized` on every call after the first, where
+    /// `embassy_rp::init` would panic.
+    pub fn take() -> Result<Self> {
+        if TAKEN.lock(|taken| taken.replace(true)) {
+            return Err(Error::HardwareAlreadyInitialized);
+        }
+        let peripherals: Peripherals = embassy_rp::init(embassy_rp::config::Config::default());
+        match Self::new(HardwareConfig::new(peripherals)) {
+            Ok(hardware) => Ok(hardware),
+            Err(err) => defmt::unreachable!("The default pins are distinct: {}", err),
+        }
+    }
 }
 
 impl Default for Hardware<'_> {
+    /// Like `Hardware::take`, for the one place that creates the hardware.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the hardware was already initialized; use `Hardware::take` to get an error
+    /// instead.
     fn default() -> Self {
-        let peripherals: Peripherals = embassy_rp::init(embassy_rp::config::Config::default());
+        match Self::take() {
+            Ok(hardware) => hardware,
+            Err(err) => defmt::panic!("{}", err),
+        }
+    }
+}
 
-        let led0 = gpio::Output::new(peripherals.PIN_2, Level::Low);
-        let led1 = gpio::Output::new(peripherals.PIN_3, Level::Low);
-        let button = gpio::Input::new(peripherals.PIN_13, gpio::Pull::Down);
-        let core1 = peripherals.CORE1;
+/// Returns the first value in `pins` that also appears earlier in `pins`.
+fn first_repeat(pins: &[u8]) -> Option<u8> {
+    pins.iter()
+        .enumerate()
+        .find_map(|(index, pin)| pins.get(..index)?.contains(pin).then_some(*pin))
+}
 
+/// Says which peripherals `Hardware::new` uses for what.
+///
+/// `embassy_rp` gives every pin its own type, so a pin can't be picked by number at runtime.
+/// Instead, the LED, potentiometer and light sensor pins are chosen at compile time through the
+/// type parameters: `led0` and `led1` must be the A and B pins of `led_slice`, and
+/// `potentiometer` and `light_sensor` ADC pins, which the compiler checks. The other pins can
+/// be any pin, so they are type-erased to `AnyPin`, and the buzzer's slice and pin are
+/// type-erased inside a `Pwm`.
+///
+/// `HardwareConfig::new` gives today's assignment. For another board, fill in the fields
+/// yourself:
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let peripherals = embassy_rp::init(embassy_rp::config::Config::default());
+/// let hardware = Hardware::new(HardwareConfig {
+///     led_slice: peripherals.PWM_SLICE3,
+///     led0: peripherals.PIN_6,
+///     led1: peripherals.PIN_7,
+///     led_onboard: peripherals.PIN_25.into(),
+///     button0: peripherals.PIN_20.into(),
+///     button1: peripherals.PIN_21.into(),
+///     button_pull: gpio::Pull::Up,
+///     buzzer: Pwm::new_output_a(peripherals.PWM_SLICE0, peripherals.PIN_16, Default::default()),
+///     adc: peripherals.ADC,
+///     potentiometer: peripherals.PIN_27,
+///     light_sensor: peripherals.PIN_28,
+///     usb: peripherals.USB,
+///     flash: peripherals.FLASH,
+///     core1: peripherals.CORE1,
+///     watchdog: peripherals.WATCHDOG,
+/// })?;
+/// ```
+pub struct HardwareConfig<
+    Slice = PWM_SLICE1,
+    Pin0 = PIN_2,
+    Pin1 = PIN_3,
+    KnobPin = PIN_26,
+    LightPin = PIN_27,
+> {
+    /// The PWM slice that drives the LEDs.
+    pub led_slice: Slice,
+    /// The pin of `led0`, channel A of `led_slice`.
+    pub led0: Pin0,
+    /// The pin of `led1`, channel B of `led_slice`.
+    pub led1: Pin1,
+    /// The pin of the heartbeat LED.
+    pub led_onboard: AnyPin,
+    /// The pin of the button that moves to the next state.
+    pub button0: AnyPin,
+    /// The pin of the button that moves to the previous state.
+    pub button1: AnyPin,
+    /// How both button pins are pulled: `Pull::Down` for buttons wired to 3.3 V, as on the
+    /// reference board, or `Pull::Up` for buttons wired to ground (with
+    /// `ButtonPolarity::ActiveLow`).
+    pub button_pull: gpio::Pull,
+    /// The buzzer's PWM output.
+    pub buzzer: Pwm<'static>,
+    /// The ADC that reads the potentiometer and the light sensor.
+    pub adc: ADC,
+    /// The pin of the potentiometer's wiper, one of GPIO 26 to 29.
+    pub potentiometer: KnobPin,
+    /// The pin of the light sensor's divider, another of GPIO 26 to 29.
+    pub light_sensor: LightPin,
+    /// The USB controller.
+    pub usb: USB,
+    /// The on-board flash.
+    pub flash: FLASH,
+    /// The second core.
+    pub core1: CORE1,
+    /// The watchdog timer.
+    pub watchdog: WATCHDOG,
+}
+
+impl HardwareConfig {
+    /// Takes the default assignment from `peripherals`: LEDs on GPIO 2 and 3 (channels A and B
+    /// of PWM slice 1), the on-board LED on GPIO 25, pulled-down buttons on GPIO 13 and 14, the
+    /// buzzer on GPIO 15 (channel B of PWM slice 7), the potentiometer on GPIO 26 and the light
+    /// sensor on GPIO 27.
+    #[must_use]
+    pub fn new(peripherals: Peripherals) -> Self {
         Self {
-            led0,
-            led1,
-            button,
-            core1,
+            led_slice: peripherals.PWM_SLICE1,
+            led0: peripherals.PIN_2,
+            led1: peripherals.PIN_3,
+            led_onboard: peripherals.PIN_25.into(),
+            button0: peripherals.PIN_13.into(),
+            button1: peripherals.PIN_14.into(),
+            button_pull: gpio::Pull::Down,
+            buzzer: Pwm::new_output_b(
+                peripherals.PWM_SLICE7,
+                peripherals.PIN_15,
+                pwm::Config::default(),
+            ),
+            adc: peripherals.ADC,
+            potentiometer: peripherals.PIN_26,
+            light_sensor: peripherals.PIN_27,
+            usb: peripherals.USB,
+            flash: peripherals.FLASH,
+            core1: peripherals.CORE1,
+            watchdog: peripherals.WATCHDOG,
         }
     }
 }
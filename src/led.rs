@@ -1,45 +1,269 @@
-use defmt::info;
-use embassy_executor::{SpawnError, Spawner};
-use embassy_futures::select::{select, Either};
-use embassy_rp::gpio::Output;
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
-use embassy_time::Timer;
+use core::{
+    cell::{Cell, RefCell},
+    future::Future,
+    num::NonZeroU32,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
 
-use crate::Schedule;
+use defmt::debug;
+use embassy_executor::{Executor, SpawnError, Spawner};
+use embassy_futures::select::{select, select4, Either, Either4};
+use embassy_rp::{
+    gpio::{Level, Output},
+    multicore::{spawn_core1, Stack},
+    peripherals::CORE1,
+};
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    channel::Channel,
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::{
+    shared_const::{
+        CORE1_STACK_SIZE, FADE_STEP_INTERVAL, FULL_BRIGHTNESS, LED_POOL_SIZE, LED_QUEUE_CAPACITY,
+        LED_SPAWN_RETRY_DELAY, SCHEDULE_CAPACITY, UNSCALED_TIME_PERMILLE, ZERO_DELAY,
+    },
+    LedOutput, PwmChannel, Schedule,
+};
 
 /// Type representing the physical LED and its "display" mode.
-pub struct Led<'a> {
-    notifier: &'a LedNotifier,
+///
+/// `N` is the capacity of the `Schedule`s the `Led` accepts. It defaults to `SCHEDULE_CAPACITY`.
+pub struct Led<'a, const N: usize = SCHEDULE_CAPACITY> {
+    notifier: &'a LedNotifier<N>,
+}
+/// Notifier that sends messages to an `Led`'s `device_loop` and shares the loop's state back.
+pub struct LedNotifier<const N: usize = SCHEDULE_CAPACITY> {
+    commands: Signal<CriticalSectionRawMutex, LedCommand<N>>,
+    is_on: AtomicBool,
+    /// Per mille factor applied to every schedule's durations; see `Led::set_time_scale`.
+    time_scale: AtomicU32,
+    /// Signaled when `time_scale` changes. Kept apart from `commands` so that a change of speed
+    /// never replaces a pending schedule.
+    rescale: Signal<CriticalSectionRawMutex, ()>,
+    /// The pin on its way to the `device_task`. A failed spawn drops the task's arguments, so
+    /// the pin is handed over here instead, where it survives for `Led::new_retry` to try again.
+    handoff: Mutex<CriticalSectionRawMutex, RefCell<Option<LedPin>>>,
+    /// When the `device_loop` next expects to flip the LED, or `None` if it doesn't.
+    next_toggle: Mutex<CriticalSectionRawMutex, Cell<Option<Instant>>>,
+    /// The schedule the `device_loop` is following, or `None` while it is idle or held.
+    current: Mutex<CriticalSectionRawMutex, RefCell<Option<Schedule<N>>>>,
+    /// Schedules to play in order, if the notifier was made with `Led::notifier_with_queue`.
+    queue: Option<&'static LedChannel<N>>,
+    /// Whether the pin is driven low to light the LED; see `LedNotifier::inverted`.
+    invert: bool,
+}
+
+/// A queue of up to `LED_QUEUE_CAPACITY` schedules that an `Led` plays one after another.
+///
+/// A schedule sent with `Led::schedule` replaces the current one at once, and if two arrive
+/// before the `device_loop` task runs, the first is lost. A schedule sent with `Led::enqueue`
+/// instead waits its turn: it starts once the current schedule is done, i.e. after its
+/// `repeat` cycles, or at the end of the current cycle for one that repeats forever. So
+/// "blink once, then go solid" is a `pulses` schedule followed by `on()`. (A schedule sent with
+/// `Led::schedule` still takes over immediately, and the queue resumes after it.)
+///
+/// The queue should be assigned to a static variable and passed to `Led::notifier_with_queue`.
+pub type LedChannel<const N: usize = SCHEDULE_CAPACITY> =
+    Channel<CriticalSectionRawMutex, Schedule<N>, LED_QUEUE_CAPACITY>;
+
+/// A message sent from an `Led` to its `device_loop`.
+#[derive(Debug)]
+pub enum LedCommand<const N: usize = SCHEDULE_CAPACITY> {
+    /// Start following a new schedule, unless it is the one already running forever.
+    Schedule(Schedule<N>),
+    /// Start following a schedule from its beginning, even if it is the one already running.
+    Restart(Schedule<N>),
+    /// Set how bright "on" is, in percent, without restarting the schedule.
+    Brightness(u8),
+    /// Set how bright "on" is, then start following a schedule as with `Schedule`, in one
+    /// command so that neither replaces the other before the `Led` takes it.
+    ScheduleAt {
+        /// The schedule to start.
+        schedule: Schedule<N>,
+        /// The brightness, in percent.
+        brightness: u8,
+    },
+    /// Stop the schedule and hold the LED at this level until the next schedule.
+    Immediate(Level),
+    /// Stop the schedule, turn the LED off and discard any queued schedules, until the next
+    /// schedule.
+    Stop,
+    /// Freeze the schedule where it is, holding the current level, until `Resume`.
+    Pause,
+    /// Continue a paused schedule from where it was frozen.
+    Resume,
+    /// Start following a new schedule, unless it is the one already running forever, with the
+    /// brightness rising from 0 to its current value over `over`.
+    SoftStart {
+        /// The schedule to start.
+        schedule: Schedule<N>,
+        /// How long the rise takes.
+        over: Duration,
+    },
+    /// Gradually change the brightness to `target` percent over `over`.
+    Fade {
+        /// The brightness to end at.
+        target: u8,
+        /// How long the fade takes.
+        over: Duration,
+    },
+}
+
+/// The pin an `Led` drives: either a plain GPIO output or a PWM channel that can be dimmed.
+pub enum LedPin {
+    /// A GPIO output. It is high whenever the LED is on with a nonzero brightness.
+    Digital(Output<'static>),
+    /// A PWM channel. Its duty cycle is the brightness whenever the LED is on.
+    Pwm(PwmChannel),
+}
+
+impl LedOutput for LedPin {
+    fn set(&mut self, brightness: u8, invert: bool) {
+        match self {
+            Self::Digital(output) => output.set_level(Level::from((brightness > 0) != invert)),
+            Self::Pwm(channel) if invert => {
+                channel.set_duty_percent(FULL_BRIGHTNESS.saturating_sub(brightness));
+            },
+            Self::Pwm(channel) => channel.set_duty_percent(brightness),
+        }
+    }
+
+    fn is_digital(&self) -> bool {
+        matches!(self, Self::Digital(_))
+    }
+}
+
+impl From<Output<'static>> for LedPin {
+    fn from(output: Output<'static>) -> Self {
+        Self::Digital(output)
+    }
+}
+
+impl From<PwmChannel> for LedPin {
+    fn from(channel: PwmChannel) -> Self {
+        Self::Pwm(channel)
+    }
 }
-/// Type alias for notifier that sends messages an `Led`.
-#[expect(
-    clippy::module_name_repetitions,
-    reason = "We use the prefix because other structs may need their own notifier type."
-)]
-pub type LedNotifier = Signal<CriticalSectionRawMutex, Schedule>;
 
 impl Led<'_> {
     /// Create a new `Led`, which entails starting an Embassy task.
     ///
     /// # Arguments
     ///
-    /// * `pin` - The pin that controls the `Led`.
+    /// * `pin` - The pin that controls the `Led`, either an `Output` or a `PwmChannel`.
     /// * `notifier` - The static notifier that sends messages to the `Led`.
-    ///          This notifier is created with the `Led::notifier()` method.
+    ///   This notifier is created with the `Led::notifier()` method.
     /// * `spawner` - The spawner that will spawn the task that controls the `Led`.
     ///
     /// # Errors
     ///
-    /// Returns a `SpawnError` if the task cannot be spawned.
+    /// Returns a `SpawnError` if the task cannot be spawned. In particular, it returns
+    /// `SpawnError::Busy` once `LED_POOL_SIZE` `Led`s already exist.
     pub fn new(
-        pin: Output<'static>,
+        pin: impl Into<LedPin>,
         notifier: &'static LedNotifier,
         spawner: Spawner,
     ) -> Result<Self, SpawnError> {
-        spawner.spawn(device_loop(pin, notifier))?;
+        notifier.hand_off(pin.into());
+        spawner.spawn(device_task(notifier))?;
         Ok(Self { notifier })
     }
 
+    /// Like `Led::new`, but if the task can't be spawned, tries again after
+    /// `LED_SPAWN_RETRY_DELAY`, up to `attempts` tries in all (at least one).
+    ///
+    /// This helps when `Led`s are created in a burst while other code is still freeing task
+    /// slots. The pin is kept between attempts.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last attempt's `SpawnError` if every attempt fails. A pool that is simply too
+    /// small (see `LED_POOL_SIZE`) fails every time, since `Led` tasks never exit.
+    #[expect(
+        clippy::future_not_send,
+        reason = "Safe in single-threaded, bare-metal embedded context"
+    )]
+    pub async fn new_retry(
+        pin: impl Into<LedPin>,
+        notifier: &'static LedNotifier,
+        spawner: Spawner,
+        attempts: u32,
+    ) -> Result<Self, SpawnError> {
+        notifier.hand_off(pin.into());
+        let mut attempt: u32 = 1;
+        loop {
+            match spawner.spawn(device_task(notifier)) {
+                Ok(()) => return Ok(Self { notifier }),
+                Err(err) if attempt >= attempts => return Err(err),
+                Err(err) => {
+                    defmt::warn!("led: spawn attempt {} failed: {}", attempt, err);
+                    attempt = attempt.saturating_add(1);
+                    Timer::after(LED_SPAWN_RETRY_DELAY).await;
+                },
+            }
+        }
+    }
+
+    /// Create a new `Led` whose task runs on the RP2040's second core.
+    ///
+    /// This starts an Embassy executor on `CORE1`, with its own `CORE1_STACK_SIZE`-byte stack,
+    /// and spawns the `Led`'s task there. The returned `Led` is used from the first core exactly
+    /// like one from `Led::new`: the notifier's `CriticalSectionRawMutex` is safe across cores.
+    ///
+    /// Taking `CORE1` by value ensures this is called at most once.
+    ///
+    /// # Panics
+    ///
+    /// Panics on the second core if the task cannot be spawned there, i.e. if `LED_POOL_SIZE`
+    /// `Led`s already exist.
+    #[must_use]
+    pub fn new_on_core1(
+        pin: impl Into<LedPin>,
+        notifier: &'static LedNotifier,
+        core1: CORE1,
+    ) -> Self {
+        notifier.hand_off(pin.into());
+        // `CORE1` is a singleton, so the stack and executor are only ever created once.
+        let Some(stack) = cortex_m::singleton!(: Stack<CORE1_STACK_SIZE> = Stack::new()) else {
+            defmt::panic!("The core 1 stack was already created");
+        };
+        spawn_core1(core1, stack, move || {
+            let Some(executor) = cortex_m::singleton!(: Executor = Executor::new()) else {
+                defmt::panic!("The core 1 executor was already created");
+            };
+            executor.run(|spawner| spawner.must_spawn(device_task(notifier)))
+        });
+        Self { notifier }
+    }
+}
+
+impl<'a, const N: usize> Led<'a, N> {
+    /// Create an `Led` whose `device_loop` is driven by a task of the caller's own.
+    ///
+    /// Embassy tasks cannot be generic, so `Led::new` can only spawn the task for the default
+    /// capacity. For any other capacity, define a task that awaits `device_loop` and spawn it
+    /// yourself.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[embassy_executor::task]
+    /// async fn long_message_task(pin: LedPin, notifier: &'static LedNotifier<64>) -> ! {
+    ///     device_loop(pin, notifier).await
+    /// }
+    ///
+    /// static LONG_NOTIFIER: LedNotifier<64> = Led::notifier();
+    /// spawner.spawn(long_message_task(hardware.led1.into(), &LONG_NOTIFIER))?;
+    /// let mut led1 = Led::from_notifier(&LONG_NOTIFIER);
+    /// ```
+    #[must_use]
+    pub const fn from_notifier(notifier: &'a LedNotifier<N>) -> Self {
+        Self { notifier }
+    }
+
     /// Creates a new `LedNotifier` instance.
     ///
     /// This notifier is used to send messages to the `Led`.
@@ -55,13 +279,371 @@ impl Led<'_> {
     /// let mut clock = Clock::new(hardware.cells, hardware.segments, &CLOCK_NOTIFIER, spawner)?;
     /// ```
     #[must_use]
-    pub const fn notifier() -> LedNotifier {
-        Signal::new()
+    pub const fn notifier() -> LedNotifier<N> {
+        Self::notifier_for(None)
+    }
+
+    /// Creates a new `LedNotifier` whose `Led` also plays the schedules sent to it with
+    /// `enqueue()`, in order, from `queue` (see `LedChannel`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// static LED_QUEUE: LedChannel = Led::channel();
+    /// static LED_NOTIFIER: LedNotifier = Led::notifier_with_queue(&LED_QUEUE);
+    /// let mut led = Led::new(hardware.led0, &LED_NOTIFIER, spawner)?;
+    /// led.enqueue(Schedule::pulses(NonZeroU32::MIN, on, off)?).await;
+    /// led.enqueue(Schedule::on()).await;
+    /// ```
+    #[must_use]
+    pub const fn notifier_with_queue(queue: &'static LedChannel<N>) -> LedNotifier<N> {
+        Self::notifier_for(Some(queue))
+    }
+
+    /// Creates a new, empty `LedChannel`, to pass to `Led::notifier_with_queue`.
+    #[must_use]
+    pub const fn channel() -> LedChannel<N> {
+        Channel::new()
+    }
+
+    const fn notifier_for(queue: Option<&'static LedChannel<N>>) -> LedNotifier<N> {
+        LedNotifier {
+            commands: Signal::new(),
+            is_on: AtomicBool::new(false),
+            time_scale: AtomicU32::new(UNSCALED_TIME_PERMILLE),
+            rescale: Signal::new(),
+            handoff: Mutex::new(RefCell::new(None)),
+            next_toggle: Mutex::new(Cell::new(None)),
+            current: Mutex::new(RefCell::new(None)),
+            queue,
+            invert: false,
+        }
+    }
+
+    /// Returns whether the `Led` is currently lit.
+    ///
+    /// This is the state the `device_loop` task last commanded, not a reading of the pin, and it
+    /// lags behind commands sent to the `Led` until the task next runs.
+    #[must_use]
+    pub fn is_on(&self) -> bool {
+        self.notifier.is_on.load(Ordering::Relaxed)
+    }
+
+    /// Returns when the current on/off segment ends and the `Led` next flips, e.g. to start a
+    /// pattern on another `Led` right on a segment boundary.
+    ///
+    /// The end of the `initial_delay` counts, as that is when the cycle flips the `Led` away from
+    /// the schedule's `start_level`.
+    /// Returns `None` when no flip is coming: while the `Led` is idle (an empty schedule, or its
+    /// repetitions used up) or held by `set_immediate()`. As with `is_on()`, the `device_loop`
+    /// task updates the value itself, so a reading can be out of date by the time it is used,
+    /// in particular right at a boundary and right after a new schedule is sent.
+    #[must_use]
+    pub fn next_toggle_at(&self) -> Option<Instant> {
+        self.notifier.next_toggle.lock(Cell::get)
+    }
+
+    /// Returns a copy of the schedule the `Led` is following, as sent (before `set_time_scale`
+    /// is applied), e.g. to log what it is doing with its `defmt::Format`. While paused, that is
+    /// still the paused schedule. Returns `None` while the `Led` is idle (its repetitions used
+    /// up, or an empty schedule) or held by `set_immediate()` or `stop()`.
+    ///
+    /// The copy costs a clone of the whole `Schedule`, up to `N` durations, taken inside a
+    /// critical section, so this is for diagnostics rather than for every loop. Like
+    /// `next_toggle_at()`, it is only as fresh as the `device_loop` task's last update: a
+    /// schedule sent but not yet picked up isn't reflected, and the task (in particular on the
+    /// other core) may move on right after this returns.
+    #[must_use]
+    pub fn current_schedule(&self) -> Option<Schedule<N>> {
+        self.notifier.current.lock(|current| current.borrow().clone())
     }
 
     /// Send a new schedule to the `led_driver` task.
-    pub fn schedule(&mut self, schedule: Schedule) {
-        self.notifier.signal(schedule);
+    ///
+    /// Returns `true` if this replaced a command that the task had not yet picked up, e.g. an
+    /// earlier schedule, which was then never shown (or a brightness change, which was lost). A
+    /// run of `true`s means schedules are being sent faster than the `Led` takes them.
+    ///
+    /// This is a diagnostic, and a racy one: the check and the send are separate steps, so the
+    /// task (in particular one on the other core) may take the old command in between, and
+    /// `true` is then a false alarm. A `false` is never wrong on a single core.
+    ///
+    /// A schedule equal to the one the `Led` is still running is ignored, so that re-entering a
+    /// state doesn't restart its pattern with a visible hiccup: the pattern carries on in phase.
+    /// (Once a schedule is done, paused, or taken over by `set_immediate()` or `stop()`, sending
+    /// it again does start it over. So does sending again one with a `repeat`, such as a second
+    /// `blink_once()` during the first, since each send asks for its own run.) An equal schedule
+    /// leaves the running pattern untouched, including any fade in progress; use `restart()` to
+    /// restart it.
+    pub fn schedule(&mut self, schedule: Schedule<N>) -> bool {
+        let pending = self.notifier.commands.signaled();
+        self.notifier.commands.signal(LedCommand::Schedule(schedule));
+        pending
+    }
+
+    /// Like `schedule()`, but starts `schedule` from its beginning even if the `Led` is already
+    /// running an equal one.
+    pub fn restart(&mut self, schedule: Schedule<N>) -> bool {
+        let pending = self.notifier.commands.signaled();
+        self.notifier.commands.signal(LedCommand::Restart(schedule));
+        pending
+    }
+
+    /// Like `schedule()`, but eases the `Led` in: the brightness starts at 0 and rises to its
+    /// current value over `over` (as by `fade_to()`), so that the LED doesn't snap straight to
+    /// full on, e.g. on entering `LedState::AlwaysOn`.
+    ///
+    /// The rise happens once, when the schedule starts, not on every cycle. Like `schedule()`, an
+    /// equal schedule that is still running is left alone, so it doesn't rise again.
+    pub fn soft_start(&mut self, schedule: Schedule<N>, over: Duration) -> bool {
+        let pending = self.notifier.commands.signaled();
+        self.notifier.commands.signal(LedCommand::SoftStart { schedule, over });
+        pending
+    }
+
+    /// Lights the `Led` once for `on` and then leaves it off, e.g. to acknowledge a button press.
+    ///
+    /// This sends a one-cycle schedule (`repeat` of 1) and returns at once: the blink runs in
+    /// the `device_loop` task, so the caller isn't held up for `on`. Like any schedule, it
+    /// replaces the current one, and a schedule sent before the blink is over cuts it short. Once
+    /// it is over, the `Led` plays the next queued schedule, if any. A zero `on` just turns the
+    /// `Led` off. Returns what `schedule()` returns.
+    pub fn blink_once(&mut self, on: Duration) -> bool {
+        let blink = Schedule::pulses(NonZeroU32::MIN, on, ZERO_DELAY).unwrap_or_default();
+        self.schedule(blink)
+    }
+
+    /// Returns whether a command (e.g. a schedule) sent to the `Led` hasn't yet been picked up by
+    /// its task, and so would be replaced by the next one. With the same caveat as `schedule()`:
+    /// the task may pick it up right after this returns `true`.
+    #[must_use]
+    pub fn has_pending_command(&self) -> bool {
+        self.notifier.commands.signaled()
+    }
+
+    /// Queues `schedule` to play once everything queued before it is done (see `LedChannel`).
+    ///
+    /// If the queue already holds `LED_QUEUE_CAPACITY` schedules, this waits for room rather
+    /// than dropping one. If the notifier has no queue, this is the same as `schedule()`.
+    pub async fn enqueue(&mut self, schedule: Schedule<N>) {
+        match self.notifier.queue {
+            Some(queue) => queue.send(schedule).await,
+            None => {
+                self.schedule(schedule);
+            },
+        }
+    }
+
+    /// Like `schedule()`, but also sets the `Led`'s brightness (as by `set_brightness()`), in
+    /// the same command. Sending the two separately would lose the brightness whenever the
+    /// schedule arrived before the `Led` took it.
+    pub fn schedule_at(&mut self, schedule: Schedule<N>, percent: u8) -> bool {
+        let pending = self.notifier.commands.signaled();
+        self.notifier.commands.signal(LedCommand::ScheduleAt {
+            schedule,
+            brightness: percent.min(FULL_BRIGHTNESS),
+        });
+        pending
+    }
+
+    /// Sets how bright the `Led` is when on, from 0 to 100 percent. Values above 100 are treated
+    /// as 100.
+    ///
+    /// The current schedule keeps running. On an `LedPin::Digital` pin, any nonzero brightness
+    /// is fully on.
+    pub fn set_brightness(&mut self, percent: u8) {
+        self.notifier.commands.signal(LedCommand::Brightness(percent.min(FULL_BRIGHTNESS)));
+    }
+
+    /// Drives the `Led` on (`Level::High`) or off (`Level::Low`) right away, outside any
+    /// schedule. The `Led` then holds that level until the next call to `schedule()`.
+    ///
+    /// "On" uses the current brightness.
+    pub fn set_immediate(&mut self, level: Level) {
+        self.notifier.commands.signal(LedCommand::Immediate(level));
+    }
+
+    /// Stops the current schedule and turns the `Led` off right away, without waiting for the
+    /// current segment to end. The `Led` stays off until the next call to `schedule()`, which
+    /// starts that schedule as usual. Schedules queued with `enqueue()` are discarded.
+    ///
+    /// The `device_loop` task turns the pin off the next time it is polled, so the latency is how
+    /// long the executor takes to get to it: normally microseconds, but longer if other tasks on
+    /// the same executor are busy.
+    pub fn stop(&mut self) {
+        self.notifier.commands.signal(LedCommand::Stop);
+    }
+
+    /// Freezes the current schedule mid-segment: the `Led` holds its current level, and the
+    /// `device_loop` task remembers how much of the segment is left.
+    ///
+    /// `resume()` continues from there rather than restarting the segment. Anything that starts
+    /// a schedule from the beginning also ends the pause: a new `schedule()` (or a queued one)
+    /// plays at once, and so does the current one when `set_time_scale()` restarts it. A fade in
+    /// progress keeps going, since it isn't part of the schedule. Pausing twice is the same as
+    /// pausing once.
+    pub fn pause(&mut self) {
+        self.notifier.commands.signal(LedCommand::Pause);
+    }
+
+    /// Continues a schedule frozen by `pause()` with whatever was left of its segment. Does
+    /// nothing if the `Led` isn't paused.
+    pub fn resume(&mut self) {
+        self.notifier.commands.signal(LedCommand::Resume);
+    }
+
+    /// Stretches (above 1000) or compresses (below 1000) every duration of this and later
+    /// schedules by `permille / 1000`, e.g. 500 runs them at twice the speed.
+    ///
+    /// The current schedule restarts at the new speed. If a schedule can't be scaled (because a
+    /// duration would overflow), it runs unscaled.
+    pub fn set_time_scale(&mut self, permille: u32) {
+        self.notifier.time_scale.store(permille, Ordering::Relaxed);
+        self.notifier.rescale.signal(());
+    }
+
+    /// Gradually changes the brightness from its current value to `target_percent` over `over`,
+    /// while the current schedule keeps running.
+    ///
+    /// The `device_loop` task updates the brightness every `FADE_STEP_INTERVAL` (10 ms) to the
+    /// linearly interpolated value, rounded toward the starting brightness. If `over` is no
+    /// longer than one step, the brightness jumps straight to `target_percent`.
+    ///
+    /// A new schedule, brightness or fade stops the fade at its current brightness.
+    ///
+    /// A digital pin (see `LedOutput::is_digital`) can't be dimmed, so during a fade it
+    /// approximates the brightness by being on for that share of the steps, which raises the
+    /// on:off ratio as the fade brightens.
+    pub fn fade_to(&mut self, target_percent: u8, over: Duration) {
+        self.notifier.commands.signal(LedCommand::Fade {
+            target: target_percent.min(FULL_BRIGHTNESS),
+            over,
+        });
+    }
+}
+
+/// A group of `COUNT` `Led`s, each running in its own task, that can be controlled together.
+///
+/// Every `Led` uses one instance of the pooled task, so `LED_POOL_SIZE` must be at least `COUNT`
+/// plus the number of `Led`s created elsewhere.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static LED_NOTIFIERS: [LedNotifier; 8] = [const { Led::notifier() }; 8];
+/// let mut leds = Leds::new(status_pins, &LED_NOTIFIERS, spawner)?;
+/// leds.schedule_all(&Schedule::slow_even());
+/// ```
+pub struct Leds<const COUNT: usize>([Led<'static>; COUNT]);
+
+impl<const COUNT: usize> Leds<COUNT> {
+    /// Creates one `Led` per pin, each with the notifier at the same index.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpawnError` if a task cannot be spawned, in particular `SpawnError::Busy` if
+    /// `LED_POOL_SIZE` is too small. The `Led`s spawned before the failure keep running.
+    pub fn new(
+        pins: [LedPin; COUNT],
+        notifiers: &'static [LedNotifier; COUNT],
+        spawner: Spawner,
+    ) -> Result<Self, SpawnError> {
+        for (pin, notifier) in pins.into_iter().zip(notifiers) {
+            notifier.hand_off(pin);
+            spawner.spawn(device_task(notifier))?;
+        }
+        Ok(Self(notifiers.each_ref().map(Led::from_notifier)))
+    }
+
+    /// Sends a copy of `schedule` to every `Led`.
+    pub fn schedule_all(&mut self, schedule: &Schedule) {
+        for led in &mut self.0 {
+            led.schedule(schedule.clone());
+        }
+    }
+
+    /// Returns the `Led` at `index`, or `None` if `index` is not less than `COUNT`.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Led<'static>> {
+        self.0.get_mut(index)
+    }
+}
+
+/// Two `Led`s driven as a pair, so that the phase between them comes from one call rather than
+/// from two hand-matched schedules.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut leds = LedPair::new(led0, led1);
+/// leds.schedule_alternate(Schedule::slow_no_delay_with(&config)?)?;
+/// ```
+pub struct LedPair<'a, const N: usize = SCHEDULE_CAPACITY> {
+    /// The first LED.
+    pub led0: Led<'a, N>,
+    /// The second LED.
+    pub led1: Led<'a, N>,
+}
+
+impl<'a, const N: usize> LedPair<'a, N> {
+    /// Pairs `led0` and `led1`.
+    #[must_use]
+    pub const fn new(led0: Led<'a, N>, led1: Led<'a, N>) -> Self {
+        Self { led0, led1 }
+    }
+
+    /// Sends `base` to both `Led`s, so that they flash in step.
+    pub fn schedule_together(&mut self, base: Schedule<N>) {
+        self.led0.schedule(base.clone());
+        self.led1.schedule(base);
+    }
+
+    /// Sends `base` to `led1` and the same schedule half a cycle later to `led0`, so that the
+    /// two `Led`s take turns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shifted schedule overflows (see `Schedule::half_cycle_later`);
+    /// neither `Led` is changed then.
+    pub fn schedule_alternate(&mut self, base: Schedule<N>) -> crate::Result<()> {
+        self.led0.schedule(base.half_cycle_later()?);
+        self.led1.schedule(base);
+        Ok(())
+    }
+
+    /// Sends `base` to `led0` and its inverse to `led1`, so that exactly one `Led` is lit at a
+    /// time (see `Schedule::mirror`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base` can't be mirrored; neither `Led` is changed then.
+    pub fn schedule_mirror(&mut self, base: Schedule<N>) -> crate::Result<()> {
+        self.led1.schedule(base.mirror()?);
+        self.led0.schedule(base);
+        Ok(())
+    }
+
+    /// Sends `schedule0` to `led0` and `schedule1` to `led1`, for a pair already worked out, e.g.
+    /// by a `Mode`.
+    pub fn schedule_each(&mut self, schedule0: Schedule<N>, schedule1: Schedule<N>) {
+        self.led0.schedule(schedule0);
+        self.led1.schedule(schedule1);
+    }
+
+    /// Like `schedule_each`, but eases both `Led`s in over `over` (see `Led::soft_start`).
+    pub fn soft_start_each(
+        &mut self,
+        schedule0: Schedule<N>,
+        schedule1: Schedule<N>,
+        over: Duration,
+    ) {
+        self.led0.soft_start(schedule0, over);
+        self.led1.soft_start(schedule1, over);
+    }
+
+    /// Sets the time scale of both `Led`s (see `Led::set_time_scale`).
+    pub fn set_time_scale(&mut self, permille: u32) {
+        self.led0.set_time_scale(permille);
+        self.led1.set_time_scale(permille);
     }
 }
 
@@ -74,39 +656,442 @@ impl Led<'_> {
 ///      blocked (!)
 /// iv) does not consume any computing cycles when "yield"ing.  Important for battery-powered and
 ///     limited-compute-capability devices.
-#[embassy_executor::task(pool_size = 4)]
-async fn device_loop(mut pin: Output<'static>, notifier: &'static LedNotifier) -> ! {
+#[embassy_executor::task(pool_size = LED_POOL_SIZE)]
+async fn device_task(notifier: &'static LedNotifier) -> ! {
+    let Some(pin) = notifier.handoff.lock(|handoff| handoff.borrow_mut().take()) else {
+        defmt::panic!("An Led task was spawned without a pin");
+    };
+    device_loop(pin, notifier).await
+}
+
+impl<const N: usize> LedNotifier<N> {
+    /// Makes this notifier's `Led` light its LED by driving the pin low, for LEDs wired
+    /// common-anode (from 3.3 V through the LED into the pin) rather than from the pin to ground.
+    ///
+    /// Schedules keep their meaning: "on" is still lit and `Led::is_on` still says whether it
+    /// is. Without this, the pin is driven high to light the LED, as on the reference board.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// static RGB_NOTIFIERS: [LedNotifier; 3] = [const { Led::notifier().inverted() }; 3];
+    /// ```
+    #[must_use]
+    pub const fn inverted(mut self) -> Self {
+        self.invert = true;
+        self
+    }
+}
+
+impl LedNotifier {
+    /// Leaves `pin` for the next `device_task` spawned with this notifier.
+    fn hand_off(&self, pin: LedPin) {
+        self.handoff.lock(|handoff| *handoff.borrow_mut() = Some(pin));
+    }
+}
+
+/// Drives `pin` according to the commands received on `notifier`, forever.
+///
+/// `Led::new` runs this inside a pooled task with an `LedPin`. Await it from a task of your own
+/// when using a non-default schedule capacity (see `Led::from_notifier`), or to drive any other
+/// `LedOutput`, such as an `embedded-hal` `OutputPin` on another MCU: the `Led` itself only
+/// holds the notifier, so it works the same whatever the pin.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[embassy_executor::task]
+/// async fn board_led_task(pin: BoardOutput, notifier: &'static LedNotifier) -> ! {
+///     device_loop(pin, notifier).await
+/// }
+///
+/// static BOARD_NOTIFIER: LedNotifier = Led::notifier();
+/// spawner.spawn(board_led_task(board.led, &BOARD_NOTIFIER))?;
+/// let mut led = Led::from_notifier(&BOARD_NOTIFIER);
+/// ```
+pub async fn device_loop<P: LedOutput, const N: usize>(
+    pin: P,
+    notifier: &'static LedNotifier<N>,
+) -> ! {
+    let mut device = Device {
+        pin,
+        notifier,
+        level: Level::Low,
+        brightness: FULL_BRIGHTNESS,
+        fade: None,
+        dither: 0,
+        paused: false,
+        running: false,
+        unscaled: Schedule::default(),
+    };
     let mut schedule = Schedule::default();
     // Drive the LED's behavior forever.
     loop {
-        // Keep the LED off the the initial delay.
-        pin.set_low(); // Turn off the LED.
-        if let Either::Second(new_schedule) =
-            select(Timer::after(schedule.initial_delay), notifier.wait()).await
-        {
-            info!("new schedule");
+        // Hold the starting level (off, unless the schedule says otherwise) for the initial
+        // delay.
+        device.set_level(schedule.start_level);
+        let toggles = !schedule.on_off_durations.is_empty();
+        if let Some(new_schedule) = device.hold_for(schedule.initial_delay, toggles).await {
             schedule = new_schedule;
             continue;
         }
 
-        // If the schedule is empty, wait for a new schedule with the LED off.
+        // If the schedule is empty, wait for a new schedule with the LED still at its starting
+        // level.
         if schedule.on_off_durations.is_empty() {
-            info!("new schedule");
-            schedule = notifier.wait().await;
+            device.set_running(false);
+            schedule = device.wait_for_schedule().await;
             continue;
         }
 
-        // Cycle forever through the schedule, toggling the LED on and off.
-        // until a new schedule is received.
-        for duration in schedule.on_off_durations.iter().cycle() {
-            pin.toggle();
-            if let Either::Second(new_schedule) =
-                select(Timer::after(*duration), notifier.wait()).await
+        // Cycle through the schedule, turning the LED on and off, until a new schedule is
+        // received or the schedule's repetitions are used up.
+        if let Some(new_schedule) = device.run_cycles(&schedule).await {
+            schedule = new_schedule;
+            continue;
+        }
+
+        // The repetitions are done, so hold the LED off until a new schedule arrives.
+        device.set_running(false);
+        device.set_level(Level::Low);
+        schedule = device.wait_for_schedule().await;
+    }
+}
+
+/// The LED as seen from inside `device_loop`.
+struct Device<P, const N: usize> {
+    pin: P,
+    notifier: &'static LedNotifier<N>,
+    /// Whether the schedule currently has the LED on (`High`) or off (`Low`).
+    level: Level,
+    brightness: u8,
+    fade: Option<Fade>,
+    /// The brightness carried over between fade steps on a digital pin.
+    dither: u8,
+    /// Whether `Led::pause()` has frozen the schedule.
+    paused: bool,
+    /// Whether `unscaled` is still playing, i.e. isn't done, held or stopped, so that sending it
+    /// again would change nothing.
+    running: bool,
+    /// The latest schedule as sent, before the time scale was applied.
+    unscaled: Schedule<N>,
+}
+
+/// A fade in progress.
+struct Fade {
+    from: u8,
+    to: u8,
+    start: Instant,
+    over: Duration,
+}
+
+impl Fade {
+    /// Returns the brightness the fade has reached, or `None` once it is done.
+    fn brightness(&self) -> Option<u8> {
+        let elapsed = self.start.elapsed().as_ticks();
+        let over = self.over.as_ticks();
+        if elapsed >= over {
+            return None;
+        }
+        let step = |span: u8| {
+            u64::from(span)
+                .checked_mul(elapsed)
+                .and_then(|ticks| ticks.checked_div(over))
+                .and_then(|step| u8::try_from(step).ok())
+                .unwrap_or(span)
+        };
+        Some(if self.to >= self.from {
+            self.from.saturating_add(step(self.to.saturating_sub(self.from)))
+        } else {
+            self.from.saturating_sub(step(self.from.saturating_sub(self.to)))
+        })
+    }
+}
+
+impl<P: LedOutput, const N: usize> Device<P, N> {
+    fn set_level(&mut self, level: Level) {
+        self.level = level;
+        self.drive();
+    }
+
+    fn set_brightness(&mut self, percent: u8) {
+        self.brightness = percent;
+        self.drive();
+    }
+
+    /// Updates the pin (and the shared `is_on` state) from the level and brightness.
+    fn drive(&mut self) {
+        let mut brightness = if self.level == Level::High {
+            self.brightness
+        } else {
+            0
+        };
+        if self.fade.is_some() && self.pin.is_digital() {
+            brightness = self.dithered(brightness);
+        }
+        self.pin.set(brightness, self.notifier.invert);
+        self.notifier.is_on.store(brightness > 0, Ordering::Relaxed);
+    }
+
+    /// Returns `FULL_BRIGHTNESS` or 0, whichever keeps the average of the steps closest to
+    /// `brightness`, for a pin that is either on or off.
+    const fn dithered(&mut self, brightness: u8) -> u8 {
+        let total = self.dither.saturating_add(brightness);
+        if total >= FULL_BRIGHTNESS {
+            self.dither = total.saturating_sub(FULL_BRIGHTNESS);
+            FULL_BRIGHTNESS
+        } else {
+            self.dither = total;
+            0
+        }
+    }
+
+    /// Applies a command that doesn't change the schedule. Returns the new schedule if the
+    /// command carries one.
+    fn apply(&mut self, command: LedCommand<N>) -> Option<Schedule<N>> {
+        // A skipped schedule leaves everything as it was, including a fade in progress.
+        if self.keeps_running(&command) {
+            debug!("led: same schedule, kept running");
+            return None;
+        }
+        // Any other command stops a fade in progress.
+        let interrupted = self.fade.take();
+        match command {
+            LedCommand::SoftStart { schedule, over } => {
+                debug!("led: soft start over {=u64}ms", over.as_millis());
+                let target = interrupted.map_or(self.brightness, |fade| fade.to);
+                self.set_brightness(0);
+                self.start_fade(target, over);
+                return Some(self.start(schedule));
+            },
+            LedCommand::Schedule(schedule) | LedCommand::Restart(schedule) => {
+                debug!("led: new schedule {}", schedule);
+                return Some(self.start(schedule));
+            },
+            LedCommand::Brightness(percent) => self.set_brightness(percent),
+            LedCommand::ScheduleAt {
+                schedule,
+                brightness,
+            } => {
+                self.set_brightness(brightness);
+                return self.apply(LedCommand::Schedule(schedule));
+            },
+            LedCommand::Immediate(level) => {
+                self.set_running(false);
+                self.set_level(level);
+            },
+            LedCommand::Stop => {
+                debug!("led: stop");
+                self.set_running(false);
+                self.set_level(Level::Low);
+                if let Some(queue) = self.notifier.queue {
+                    queue.clear();
+                }
+            },
+            LedCommand::Pause => {
+                debug!("led: pause");
+                self.paused = true;
+            },
+            LedCommand::Resume => {
+                debug!("led: resume");
+                self.paused = false;
+            },
+            LedCommand::Fade { target, over } => self.start_fade(target, over),
+        }
+        None
+    }
+
+    /// Returns whether `command` is a `Schedule` (or `SoftStart`) equal to the one running
+    /// forever, which `apply` skips.
+    fn keeps_running(&self, command: &LedCommand<N>) -> bool {
+        let (LedCommand::Schedule(schedule) | LedCommand::SoftStart { schedule, .. }) = command
+        else {
+            return false;
+        };
+        self.running && !self.paused && schedule.repeat.is_none() && *schedule == self.unscaled
+    }
+
+    /// Starts fading from the current brightness to `target` over `over`, or jumps straight
+    /// there if `over` is no longer than one step.
+    fn start_fade(&mut self, target: u8, over: Duration) {
+        if over <= FADE_STEP_INTERVAL {
+            self.set_brightness(target);
+        } else {
+            self.fade = Some(Fade {
+                from: self.brightness,
+                to: target,
+                start: Instant::now(),
+                over,
+            });
+        }
+    }
+
+    /// Makes `schedule` the one being followed, unpaused, and returns it scaled.
+    fn start(&mut self, schedule: Schedule<N>) -> Schedule<N> {
+        self.paused = false;
+        self.unscaled = schedule;
+        self.set_running(true);
+        self.scaled()
+    }
+
+    /// Records whether `unscaled` is still playing, for `apply` and `Led::current_schedule`.
+    fn set_running(&mut self, running: bool) {
+        self.running = running;
+        let current = running.then(|| self.unscaled.clone());
+        self.notifier.current.lock(|cell| *cell.borrow_mut() = current);
+    }
+
+    /// Returns the latest schedule scaled by the notifier's time scale.
+    fn scaled(&self) -> Schedule<N> {
+        let permille = self.notifier.time_scale.load(Ordering::Relaxed);
+        if permille == UNSCALED_TIME_PERMILLE {
+            return self.unscaled.clone();
+        }
+        self.unscaled
+            .scaled(permille, UNSCALED_TIME_PERMILLE)
+            .unwrap_or_else(|_| self.unscaled.clone())
+    }
+
+    /// Moves a fade in progress one step along.
+    fn step_fade(&mut self) {
+        let Some(fade) = &self.fade else {
+            return;
+        };
+        let (brightness, fade_done) =
+            fade.brightness().map_or((fade.to, true), |brightness| (brightness, false));
+        // Clear a finished fade first, so that the last step isn't dithered.
+        if fade_done {
+            self.fade = None;
+        }
+        self.set_brightness(brightness);
+    }
+
+    /// Holds the current level until `end` (or forever if `None`), stepping any fade in
+    /// progress. The end is published as the next toggle if `toggles`.
+    ///
+    /// While paused, the time left until `end` is set aside and the hold lasts until resumed;
+    /// then `end` is that much time from the resume.
+    ///
+    /// Returns the new schedule if one arrives first (including from the queue, if `dequeue`),
+    /// or the current one rescaled if the time scale changes first while it is still running.
+    async fn hold(
+        &mut self,
+        mut end: Option<Instant>,
+        toggles: bool,
+        dequeue: bool,
+    ) -> Option<Schedule<N>> {
+        let notifier = self.notifier;
+        let mut remaining = None;
+        self.publish_next_toggle(end.filter(|_| toggles));
+        loop {
+            if self.paused {
+                if let Some(until_end) = end.take() {
+                    remaining = Some(until_end.saturating_duration_since(Instant::now()));
+                    self.publish_next_toggle(None);
+                }
+            } else if let Some(left) = remaining.take() {
+                end = Some(Instant::now().checked_add(left).unwrap_or(Instant::MAX));
+                self.publish_next_toggle(end.filter(|_| toggles));
+            }
+            let fade_step = self.fade.as_ref().map(|_| Timer::after(FADE_STEP_INTERVAL));
+            match select4(
+                until(end.map(Timer::at)),
+                next_command(notifier, dequeue),
+                until(fade_step),
+                notifier.rescale.wait(),
+            )
+            .await
             {
-                info!("new schedule");
-                schedule = new_schedule;
-                break;
+                Either4::First(()) => return None,
+                Either4::Second(command) => {
+                    let holds = matches!(command, LedCommand::Immediate(_) | LedCommand::Stop);
+                    if let Some(schedule) = self.apply(command) {
+                        return Some(schedule);
+                    }
+                    // An immediate level (or a stop) is held until the next schedule.
+                    if holds {
+                        end = None;
+                        remaining = None;
+                        self.publish_next_toggle(None);
+                    }
+                },
+                Either4::Third(()) => self.step_fade(),
+                // A stopped or finished schedule stays stopped; the new scale is read when the
+                // next one starts.
+                Either4::Fourth(()) if self.running => {
+                    self.paused = false;
+                    return Some(self.scaled());
+                },
+                Either4::Fourth(()) => {},
             }
         }
     }
+
+    /// Holds the current level for `duration`, like `hold`, publishing its end as the next
+    /// toggle if `toggles`.
+    async fn hold_for(&mut self, duration: Duration, toggles: bool) -> Option<Schedule<N>> {
+        let end = Instant::now().checked_add(duration).unwrap_or(Instant::MAX);
+        self.hold(Some(end), toggles, false).await
+    }
+
+    fn publish_next_toggle(&self, next_toggle: Option<Instant>) {
+        self.notifier.next_toggle.lock(|cell| cell.set(next_toggle));
+    }
+
+    /// Holds the current level until a new schedule arrives, or the next one is queued.
+    async fn wait_for_schedule(&mut self) -> Schedule<N> {
+        loop {
+            if let Some(schedule) = self.hold(None, false, true).await {
+                return schedule;
+            }
+        }
+    }
+
+    /// Runs the schedule's on/off cycle `schedule.repeat` times (or forever if `None`).
+    ///
+    /// Returns the new schedule if one arrives before the repetitions are done. A schedule that
+    /// repeats forever also gives way, at the end of a cycle, to the next queued schedule.
+    async fn run_cycles(&mut self, schedule: &Schedule<N>) -> Option<Schedule<N>> {
+        let repeat = schedule.repeat.map(NonZeroU32::get);
+        let mut completed: u32 = 0;
+        while repeat.is_none_or(|cycles| completed < cycles) {
+            for (level, duration) in schedule.steps() {
+                self.set_level(level);
+                if let Some(new_schedule) = self.hold_for(duration, true).await {
+                    return Some(new_schedule);
+                }
+            }
+            completed = completed.saturating_add(1);
+            if repeat.is_none() {
+                if let Some(queued) = self.notifier.queue.and_then(|queue| queue.try_receive().ok())
+                {
+                    // Even one equal to the current schedule starts afresh, as its turn has come.
+                    return self.apply(LedCommand::Restart(queued));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Waits for the next command on `notifier`, also taking a queued schedule if `dequeue`.
+async fn next_command<const N: usize>(
+    notifier: &'static LedNotifier<N>,
+    dequeue: bool,
+) -> LedCommand<N> {
+    match notifier.queue.filter(|_| dequeue) {
+        Some(queue) => match select(notifier.commands.wait(), queue.receive()).await {
+            Either::First(command) => command,
+            Either::Second(schedule) => LedCommand::Schedule(schedule),
+        },
+        None => notifier.commands.wait().await,
+    }
+}
+
+/// Waits for `timer`, or forever if there is none.
+async fn until(timer: Option<impl Future<Output = ()>>) {
+    match timer {
+        Some(expiry) => expiry.await,
+        None => core::future::pending().await,
+    }
 }
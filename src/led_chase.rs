@@ -0,0 +1,60 @@
+use embassy_time::Duration;
+
+use crate::{
+    error::{Error, Result},
+    Led, Schedule,
+};
+
+/// A group of `Led`s that show the same schedule one after another, as in a running light.
+///
+/// `start()` spaces the `Led`s evenly across the schedule's cycle: with four `Led`s, the second
+/// runs a quarter cycle behind the first, the third half a cycle behind and so on. This is
+/// `LedState::FastAlternate`'s trick for two `Led`s, for any number of them. A schedule whose
+/// "on" part is one step of the cycle (e.g. `Schedule::duty_cycle(period, 100 / count)`) gives
+/// the classic single running dot.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut leds = [&mut led0, &mut led1, &mut led2, &mut led3];
+/// let mut chase = LedChase::new(&mut leds);
+/// chase.start(&Schedule::duty_cycle(Duration::from_millis(800), 25)?)?;
+/// ```
+pub struct LedChase<'a> {
+    leds: &'a mut [&'a mut Led<'static>],
+}
+
+impl<'a> LedChase<'a> {
+    /// Creates a new `LedChase` that lights `leds` in order.
+    #[must_use]
+    pub const fn new(leds: &'a mut [&'a mut Led<'static>]) -> Self {
+        Self { leds }
+    }
+
+    /// Sends each `Led` a copy of `base`, phase-shifted by its share of the cycle (see
+    /// `Schedule::phase_shifted`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cycle's duration or one of the shifted `initial_delay`s
+    /// overflows. In that case no `Led` is changed.
+    pub fn start(&mut self, base: &Schedule) -> Result<()> {
+        let cycle = base.cycle_duration()?.as_ticks();
+        let count = u64::try_from(self.leds.len()).map_err(|_| Error::ArithmeticOverflow)?;
+        let shift = |index: u64| {
+            cycle
+                .checked_mul(index)
+                .and_then(|ticks| ticks.checked_div(count))
+                .map(Duration::from_ticks)
+                .ok_or(Error::ArithmeticOverflow)
+        };
+        // The last `Led` has the largest shift, so if its schedule can be built, so can the rest.
+        if let Some(last) = count.checked_sub(1) {
+            base.phase_shifted(shift(last)?)?;
+        }
+        for (index, led) in (0..).zip(self.leds.iter_mut()) {
+            led.schedule(base.phase_shifted(shift(index)?)?);
+        }
+        Ok(())
+    }
+}
@@ -1,128 +1,591 @@
+use core::pin::pin;
+
+use embassy_futures::select::{select, Either};
+use embassy_rp::gpio::{Input, Level};
+use embassy_time::{Duration, Timer};
+
 use crate::{
-    button::{Button, PressDuration},
+    button::{press_or_chord, Button, PairPress, PressDuration},
     error::Result,
-    led::Led,
-    Schedule,
+    led::LedPair,
+    shared_const::{SPEED_CYCLE_PERMILLE, UNSCALED_TIME_PERMILLE},
+    AppConfig, Buzzer, InputPin, MorseMessage, Power, Schedule, StateMachine, StateStore,
 };
 
 /// Represents the different states the LEDs can operate in.
 ///
-/// For example, an `Led` in `Sos` state sends the Morse code distress signal.
+/// For example, an `Led` in `Sos` state sends the Morse code distress signal, and in `AllMorse`
+/// state both `Led`s send a `MorseMessage`, while in `DualMorse` each sends its own.
+/// `SpeedCycle` alternates the LEDs like `SlowAlternate`, at the speed `level` picks from
+/// `SPEED_CYCLE_PERMILLE`. `SosArmed` strobes both LEDs while it waits for a press to confirm
+/// `Sos`, then falls back to the state whose `as_index()` is `from`.
 #[expect(missing_docs, reason = "We don't need to document the variants of this enum.")]
-#[derive(Debug, defmt::Format, Default)]
+#[derive(Clone, Copy, Debug, defmt::Format, Default, Eq, PartialEq)]
 pub enum LedState {
     #[default]
     FastAlternate,
     FastTogether,
     SlowAlternate,
+    SpeedCycle {
+        level: u8,
+    },
+    Breathing,
     Sos,
     AlwaysOn,
     AlwaysOff,
+    AllMorse,
+    DualMorse,
+    SosArmed {
+        from: u8,
+    },
 }
 
 impl LedState {
+    /// The state machine, one row per state, in `to_byte()` order.
+    ///
+    /// To add a state, add a variant, give it a byte, add its row here and its schedules to
+    /// `schedules()`.
+    ///
+    /// In `SpeedCycle`, presses on `button0` change the speed instead (see `next_short()`), so
+    /// its `next` is only what `next()` reports, e.g. for the idle timeout's display cycle.
+    /// `SosArmed` is entered and left by `transition()` rather than by this table, so its row
+    /// only says where `next()` and `prev()` go.
+    #[rustfmt::skip]
+    const TRANSITIONS: [Transition; 11] = [
+        //              state                next                 previous             very_long
+        Transition::new(Self::FastAlternate, Self::FastTogether,  Self::AlwaysOff,     Self::Sos),
+        Transition::new(Self::FastTogether,  Self::SlowAlternate, Self::FastAlternate, Self::Sos),
+        Transition::new(Self::SlowAlternate, Self::SPEED_CYCLE,   Self::FastTogether,  Self::Sos),
+        Transition::new(Self::Breathing,     Self::AllMorse,      Self::SPEED_CYCLE,   Self::Sos),
+        Transition::new(Self::AlwaysOn,      Self::AlwaysOff,     Self::DualMorse,     Self::Sos),
+        Transition::new(Self::AlwaysOff,     Self::FastAlternate, Self::AlwaysOn,      Self::Sos),
+        Transition::new(Self::Sos,           Self::FastAlternate, Self::AlwaysOff,     Self::Sos),
+        Transition::new(Self::AllMorse,      Self::DualMorse,     Self::Breathing,     Self::Sos),
+        Transition::new(Self::SPEED_CYCLE,   Self::Breathing,     Self::SlowAlternate, Self::Sos),
+        Transition::new(Self::DualMorse,     Self::AlwaysOn,      Self::AllMorse,      Self::Sos),
+        Transition::new(Self::SOS_ARMED,     Self::FastAlternate, Self::AlwaysOff,     Self::Sos),
+    ];
+
+    /// `SpeedCycle` at its slowest, as it is entered.
+    const SPEED_CYCLE: Self = Self::SpeedCycle { level: 0 };
+
+    /// The all-dark safe state that a very long press on `button1` jumps to from any state,
+    /// including `Sos` and `SosArmed`: both LEDs off (whatever `ModeTable` says) and the chip
+    /// asleep until a press wakes it.
+    pub const BLACKOUT: Self = Self::AlwaysOff;
+
+    /// `SosArmed` from the default state, e.g. for its row of `TRANSITIONS`.
+    const SOS_ARMED: Self = Self::SosArmed { from: 0 };
+
+    /// Every state, in `as_index()` order. (`SpeedCycle` is listed at its first level.)
+    const ALL: [Self; 11] = [
+        Self::FastAlternate,
+        Self::FastTogether,
+        Self::SlowAlternate,
+        Self::SPEED_CYCLE,
+        Self::Breathing,
+        Self::AllMorse,
+        Self::DualMorse,
+        Self::AlwaysOn,
+        Self::AlwaysOff,
+        Self::Sos,
+        Self::SOS_ARMED,
+    ];
+
+    /// Returns every state, in `as_index()` order, e.g. to walk the state graph with
+    /// `next_short()`, `next_long()` and `next_very_long()`.
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    /// Loads the state saved by `save()`, falling back to the default state on first boot (or
+    /// if the stored value can't be read or isn't a valid state).
+    pub fn load(state_store: &mut StateStore<'_>) -> Self {
+        state_store.read().ok().and_then(Self::from_byte).unwrap_or_default()
+    }
+
+    /// Saves the state to flash so that `load()` can restore it after a power cycle.
+    ///
+    /// `SosArmed` saves the state it was armed from, so a pending `Sos` doesn't survive a power
+    /// cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flash cannot be written.
+    pub fn save(&self, state_store: &mut StateStore<'_>) -> Result<()> {
+        state_store.write(self.settled().to_byte())
+    }
+
+    /// Returns the state's position in the forward cycle: `FastAlternate` is 0, `FastTogether`
+    /// 1 and so on through `AlwaysOff` (8), then `Sos` (9) and `SosArmed` (10), which are outside
+    /// the cycle. Every `SpeedCycle` level has the same index, and so does every `SosArmed`.
+    ///
+    /// This is meant for displays and protocols. It is not what `save()` stores, so that
+    /// reordering the cycle never misreads a state saved by older firmware.
+    #[must_use]
+    pub const fn as_index(&self) -> u8 {
+        match self {
+            Self::FastAlternate => 0,
+            Self::FastTogether => 1,
+            Self::SlowAlternate => 2,
+            Self::SpeedCycle { .. } => 3,
+            Self::Breathing => 4,
+            Self::AllMorse => 5,
+            Self::DualMorse => 6,
+            Self::AlwaysOn => 7,
+            Self::AlwaysOff => 8,
+            Self::Sos => 9,
+            Self::SosArmed { .. } => 10,
+        }
+    }
+
+    /// Returns the state at `index` as numbered by `as_index()`, or `None` if there is none.
+    /// `SpeedCycle` comes back at its first level, and `SosArmed` as armed from the default
+    /// state.
+    #[must_use]
+    pub const fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Self::FastAlternate),
+            1 => Some(Self::FastTogether),
+            2 => Some(Self::SlowAlternate),
+            3 => Some(Self::SPEED_CYCLE),
+            4 => Some(Self::Breathing),
+            5 => Some(Self::AllMorse),
+            6 => Some(Self::DualMorse),
+            7 => Some(Self::AlwaysOn),
+            8 => Some(Self::AlwaysOff),
+            9 => Some(Self::Sos),
+            10 => Some(Self::SOS_ARMED),
+            _ => None,
+        }
+    }
+
+    /// The byte `save()` stores. `SpeedCycle`'s level isn't stored, so it restarts slow.
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::FastAlternate => 0,
+            Self::FastTogether => 1,
+            Self::SlowAlternate => 2,
+            Self::Breathing => 3,
+            Self::AlwaysOn => 4,
+            Self::AlwaysOff => 5,
+            Self::Sos => 6,
+            Self::AllMorse => 7,
+            Self::SpeedCycle { .. } => 8,
+            Self::DualMorse => 9,
+            Self::SosArmed { .. } => 10,
+        }
+    }
+
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::FastAlternate),
+            1 => Some(Self::FastTogether),
+            2 => Some(Self::SlowAlternate),
+            3 => Some(Self::Breathing),
+            4 => Some(Self::AlwaysOn),
+            5 => Some(Self::AlwaysOff),
+            6 => Some(Self::Sos),
+            7 => Some(Self::AllMorse),
+            8 => Some(Self::SPEED_CYCLE),
+            9 => Some(Self::DualMorse),
+            10 => Some(Self::SOS_ARMED),
+            _ => None,
+        }
+    }
+
     /// Runs the current LED state and returns the next state.
     ///
+    /// This shows the state's schedules from `config.modes` (see `ModeTable`), waits for a press
+    /// and returns where `TRANSITIONS` leads (see `transition()`). If `config.idle_timeout`
+    /// passes first, it returns `next()` instead. `Sos` and `AlwaysOff` never time out, and `Sos`
+    /// ignores short taps, so that a stray touch can't end it.
+    ///
+    /// `Sos` also sounds `led0`'s pattern on `buzzer`, and `AlwaysOff` puts the chip to sleep
+    /// until a press (see `Power`). A very long press logs both LEDs' schedules, and with the
+    /// `event-log` feature, the recent transitions. Dropping the future mid-tone leaves `buzzer`
+    /// sounding, as with `Buzzer::play`.
+    ///
     /// # Errors
     ///
     /// This function will return an error if scheduling the LED state fails.
-    pub async fn execute(
+    pub async fn execute<P0: InputPin, P1: InputPin>(
         self,
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
+        leds: &mut LedPair<'_>,
+        button0: &mut Button<P0>,
+        button1: &mut Button<P1>,
+        messages: [&MorseMessage; 2],
+        buzzer: &mut Buzzer,
+        config: &AppConfig,
     ) -> Result<Self> {
+        let (schedule0, schedule1) = self.schedules(messages, config)?;
+        let sound = (self == Self::Sos).then(|| schedule0.clone());
+        if self == Self::AlwaysOn {
+            leds.soft_start_each(schedule0, schedule1, config.soft_start_duration);
+        } else {
+            leds.schedule_each(schedule0, schedule1);
+        }
+        if self == Self::AlwaysOff {
+            Power::prepare_sleep(leds).await;
+            Power::sleep_until_pressed(button0, button1).await;
+        }
+
+        let timeout = match self {
+            Self::SosArmed { .. } => Some(config.sos_arm_window),
+            Self::Sos | Self::AlwaysOff => None,
+            _ => config.idle_timeout,
+        };
+        let mut press = pin!(self.awaited_press(button0, button1, timeout));
+        // `None` if the timeout passed without a press.
+        let pair_press = match sound {
+            Some(schedule) => match select(&mut press, buzzer.play(&schedule)).await {
+                Either::First(pressed) => pressed,
+                Either::Second(()) => press.await,
+            },
+            None => press.await,
+        };
+        buzzer.set_level(Level::Low);
+        let very_long = matches!(
+            pair_press,
+            Some(
+                PairPress::First(PressDuration::VeryLong)
+                    | PairPress::Second(PressDuration::VeryLong)
+            )
+        );
+        if very_long {
+            defmt::info!(
+                "state: led0 running {}, led1 running {}",
+                leds.led0.current_schedule(),
+                leds.led1.current_schedule()
+            );
+        }
+        let next = pair_press.map_or_else(|| self.timed_out(), |pressed| self.transition(pressed));
+        defmt::info!("state: {} -> {} after {}", self, next, pair_press);
+        #[cfg(feature = "event-log")]
+        {
+            crate::event_log::record(self, pair_press, next);
+            if very_long {
+                crate::dump_log();
+            }
+        }
+        Ok(next)
+    }
+
+    /// Returns the schedules for `led0` and `led1` in this state, from `config.modes`.
+    ///
+    /// In `SpeedCycle`, the mode's schedules are also scaled by the level's
+    /// `SPEED_CYCLE_PERMILLE`. `BLACKOUT` is `Schedule::off()` on both, whatever the table says,
+    /// as it is the safe state and the chip sleeps in it anyway.
+    fn schedules(
+        self,
+        messages: [&MorseMessage; 2],
+        config: &AppConfig,
+    ) -> Result<(Schedule, Schedule)> {
+        if self == Self::BLACKOUT {
+            return Ok((Schedule::off(), Schedule::off()));
+        }
+        let (schedule0, schedule1) = config.modes.mode(self).schedules(messages, config)?;
+        let Self::SpeedCycle { level } = self else {
+            return Ok((schedule0, schedule1));
+        };
+        let permille =
+            SPEED_CYCLE_PERMILLE.get(usize::from(level)).copied().unwrap_or(UNSCALED_TIME_PERMILLE);
+        Ok((
+            schedule0.scaled(permille, UNSCALED_TIME_PERMILLE)?,
+            schedule1.scaled(permille, UNSCALED_TIME_PERMILLE)?,
+        ))
+    }
+
+    /// Returns the state that going `idle_timeout` without a press leads to: `next()`, except
+    /// that `SosArmed` lapses back to the state it was armed from.
+    fn timed_out(self) -> Self {
         match self {
-            Self::FastAlternate => Self::execute_fast_alternate(led0, led1, button).await,
-            Self::FastTogether => Self::execute_fast_together(led0, led1, button).await,
-            Self::SlowAlternate => Self::execute_slow_alternate(led0, led1, button).await,
-            Self::Sos => Self::execute_sos(led0, led1, button).await,
-            Self::AlwaysOn => Self::execute_always_on(led0, led1, button).await,
-            Self::AlwaysOff => Self::execute_always_off(led0, led1, button).await,
+            Self::SosArmed { .. } => self.settled(),
+            _ => self.next(),
         }
     }
 
-    #[inline]
-    async fn execute_fast_alternate(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::fast_with_delay()?);
-        led1.schedule(Schedule::fast_no_delay()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::FastTogether),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Returns the state after this one in the cycle.
+    #[must_use]
+    pub fn next(self) -> Self {
+        self.row().map_or_else(Self::default, |row| row.next)
+    }
+
+    /// Returns the state before this one in the cycle, mirroring `next()`.
+    #[must_use]
+    pub fn prev(self) -> Self {
+        self.row().map_or_else(Self::default, |row| row.previous)
+    }
+
+    /// Returns the state that a short press on `button0` leads to from this state: `next()`,
+    /// except that `SpeedCycle` moves to its next level, wrapping around.
+    ///
+    /// A short press on `button1` leads to `prev()` instead.
+    #[must_use]
+    pub fn next_short(self) -> Self {
+        match self {
+            Self::SpeedCycle { level } => {
+                let next_level = level.saturating_add(1);
+                if usize::from(next_level) < SPEED_CYCLE_PERMILLE.len() {
+                    Self::SpeedCycle { level: next_level }
+                } else {
+                    Self::SPEED_CYCLE
+                }
+            },
+            _ => self.next(),
         }
     }
 
-    #[inline]
-    async fn execute_fast_together(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::fast_with_delay()?);
-        led1.schedule(Schedule::fast_with_delay()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::SlowAlternate),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Returns the state that a long press on either button leads to from this state: `prev()`,
+    /// except that `SpeedCycle` heads for `Sos` (which `execute()` arms first).
+    #[must_use]
+    pub fn next_long(self) -> Self {
+        match self {
+            Self::SpeedCycle { .. } => Self::Sos,
+            _ => self.prev(),
         }
     }
 
-    #[inline]
-    async fn execute_slow_alternate(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::slow_even()?);
-        led1.schedule(Schedule::slow_no_delay()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::AlwaysOn),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Returns the state that a very long press on `button0` leads to from this state, as listed
+    /// in `TRANSITIONS`. (`execute()` arms a move to `Sos` before making it.) A very long press
+    /// on `button1` leads to `BLACKOUT` instead.
+    #[must_use]
+    pub fn next_very_long(self) -> Self {
+        self.row().map_or_else(Self::default, |row| row.very_long)
+    }
+
+    /// Looks up the state that a press leads to from this state.
+    ///
+    /// A short press on `button0` moves to `next_short()`, while a short press on `button1` goes
+    /// to `prev()` and a long press on either to `next_long()`. A very long press on `button0`
+    /// goes to `next_very_long()`, and one on `button1` to `BLACKOUT`, from any state. A move
+    /// toward `Sos` only arms it: `SosArmed` is confirmed by a long press and left by any other.
+    /// A chord (see `press_or_chord`) enters `Sos` at once.
+    ///
+    /// This is the whole of `execute()`'s decision; everything else there is waiting for the
+    /// press and driving the hardware.
+    fn transition(self, pair_press: PairPress) -> Self {
+        let long = matches!(
+            pair_press,
+            PairPress::First(PressDuration::Long | PressDuration::VeryLong)
+                | PairPress::Second(PressDuration::Long | PressDuration::VeryLong)
+        );
+        if let Self::SosArmed { from } = self {
+            return if pair_press == PairPress::Second(PressDuration::VeryLong) {
+                Self::BLACKOUT
+            } else if long || pair_press == PairPress::Chord {
+                Self::Sos
+            } else {
+                Self::from_index(from).unwrap_or_default()
+            };
+        }
+        let next = match pair_press {
+            PairPress::First(PressDuration::Short) => self.next_short(),
+            PairPress::Second(PressDuration::Short) => self.prev(),
+            PairPress::First(PressDuration::Long) | PairPress::Second(PressDuration::Long) => {
+                self.next_long()
+            },
+            PairPress::First(PressDuration::VeryLong) => self.next_very_long(),
+            PairPress::Second(PressDuration::VeryLong) => Self::BLACKOUT,
+            PairPress::Chord => return Self::Sos,
+        };
+        // A press (other than a chord) toward `Sos` only arms it.
+        if next == Self::Sos && self != Self::Sos {
+            Self::SosArmed {
+                from: self.as_index(),
+            }
+        } else {
+            next
         }
     }
 
-    #[inline]
-    async fn execute_sos(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::sos_slow()?);
-        led1.schedule(Schedule::sos_fast()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::FastAlternate),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Returns the state to save or resume: `SosArmed`'s `from`, or else this state.
+    fn settled(self) -> Self {
+        match self {
+            Self::SosArmed { from } => Self::from_index(from).unwrap_or_default(),
+            _ => self,
         }
     }
 
-    #[inline]
-    async fn execute_always_on(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::on()?);
-        led1.schedule(Schedule::on()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::AlwaysOff),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Returns this state's row of `TRANSITIONS`.
+    ///
+    /// Every state has a row at its `to_byte()` index (checked at compile time below), so this
+    /// never returns `None`.
+    fn row(self) -> Option<&'static Transition> {
+        Self::TRANSITIONS.get(usize::from(self.to_byte()))
+    }
+
+    /// Waits for the press that `transition()` acts on: in `Sos`, a long (or very long) press on
+    /// either button, ignoring short taps (see `Button::wait_for_long_press`); in any other
+    /// state, whatever `press_or_idle` returns.
+    async fn awaited_press<P0: InputPin, P1: InputPin>(
+        self,
+        button0: &mut Button<P0>,
+        button1: &mut Button<P1>,
+        idle_timeout: Option<Duration>,
+    ) -> Option<PairPress> {
+        if self != Self::Sos {
+            return Self::press_or_idle(button0, button1, idle_timeout).await;
         }
+        Some(match select(button0.wait_for_long_press(), button1.wait_for_long_press()).await {
+            Either::First(press_duration) => PairPress::First(press_duration),
+            Either::Second(press_duration) => PairPress::Second(press_duration),
+        })
     }
 
-    #[inline]
-    async fn execute_always_off(
-        led0: &mut Led<'_>,
-        led1: &mut Led<'_>,
-        button: &mut Button<'_>,
-    ) -> Result<Self> {
-        led0.schedule(Schedule::off()?);
-        led1.schedule(Schedule::off()?);
-        match button.press_duration().await {
-            PressDuration::Short => Ok(Self::FastAlternate),
-            PressDuration::Long => Ok(Self::Sos),
+    /// Waits for a press on either button (or both, see `press_or_chord`), or returns `None` if
+    /// `idle_timeout` passes without one.
+    async fn press_or_idle<P0: InputPin, P1: InputPin>(
+        button0: &mut Button<P0>,
+        button1: &mut Button<P1>,
+        idle_timeout: Option<Duration>,
+    ) -> Option<PairPress> {
+        let Some(timeout) = idle_timeout else {
+            return Some(press_or_chord(button0, button1).await);
+        };
+        match select(press_or_chord(button0, button1), Timer::after(timeout)).await {
+            Either::First(pair_press) => Some(pair_press),
+            Either::Second(()) => None,
+        }
+    }
+}
+
+/// Everything an `LedState` drives and reads as it runs; the `StateMachine::Context` of
+/// `LedState`.
+///
+/// The fields are public so that the driver loop can also use them between steps, e.g. to
+/// change the LEDs' speed.
+pub struct LedContext<P0 = Input<'static>, P1 = Input<'static>> {
+    /// The two LEDs.
+    pub leds: LedPair<'static>,
+    /// The button that moves to the next state.
+    pub button0: Button<P0>,
+    /// The button that moves to the previous state.
+    pub button1: Button<P1>,
+    /// The messages flashed in the `AllMorse` (first only) and `DualMorse` states.
+    pub messages: [&'static MorseMessage; 2],
+    /// The buzzer that sounds `led0`'s pattern in `Sos`.
+    pub buzzer: Buzzer,
+    /// The flash delays and idle timeout.
+    pub config: AppConfig,
+}
+
+impl StateMachine for LedState {
+    type Context = LedContext;
+
+    /// Runs the state with `LedState::execute`, on the parts in `context`.
+    async fn step(self, context: &mut LedContext) -> Result<Self> {
+        self.execute(
+            &mut context.leds,
+            &mut context.button0,
+            &mut context.button1,
+            context.messages,
+            &mut context.buzzer,
+            &context.config,
+        )
+        .await
+    }
+}
+
+// Check that `LedState::TRANSITIONS` has each state's row at the state's `to_byte()` index.
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    let mut index = 0;
+    let mut byte = 0;
+    while index < LedState::TRANSITIONS.len() {
+        assert!(
+            LedState::TRANSITIONS[index].state.to_byte() == byte,
+            "TRANSITIONS is out of order"
+        );
+        index += 1;
+        byte += 1;
+    }
+};
+
+// Check that `LedState::from_index` and `LedState::as_index` are inverses.
+const _: () = {
+    let mut index = 0;
+    while index < LedState::TRANSITIONS.len() {
+        #[expect(clippy::cast_possible_truncation, reason = "There are only eleven states.")]
+        let found = LedState::from_index(index as u8);
+        assert!(
+            matches!(found, Some(state) if state.as_index() as usize == index),
+            "as_index and from_index disagree"
+        );
+        index += 1;
+    }
+};
+
+// Check that `LedState::ALL` lists every state once, in `as_index()` order.
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    let mut index = 0;
+    while index < LedState::ALL.len() {
+        assert!(LedState::ALL[index].as_index() as usize == index, "ALL is out of order");
+        index += 1;
+    }
+};
+
+// Check that every state can be reached from the default state by some sequence of presses, i.e.
+// that walking `TRANSITIONS` (which `next_short()`, `prev()`, `next_long()` and
+// `next_very_long()` read) from `LedState::default()` visits every row. `SosArmed`, which
+// `transition()` puts in front of every way into `Sos`, is counted as reached along with `Sos`.
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    let mut reached = [false; LedState::TRANSITIONS.len()];
+    reached[LedState::FastAlternate.to_byte() as usize] = true;
+    reached[LedState::SOS_ARMED.to_byte() as usize] = true;
+    // Each pass reaches at least one more state until none are left, so `len()` passes suffice.
+    let mut pass = 0;
+    while pass < LedState::TRANSITIONS.len() {
+        let mut index = 0;
+        while index < LedState::TRANSITIONS.len() {
+            if reached[index] {
+                let row = &LedState::TRANSITIONS[index];
+                reached[row.next.to_byte() as usize] = true;
+                reached[row.previous.to_byte() as usize] = true;
+                reached[row.very_long.to_byte() as usize] = true;
+            }
+            index += 1;
+        }
+        pass += 1;
+    }
+    let mut index = 0;
+    while index < reached.len() {
+        assert!(reached[index], "A state can't be reached from the default state");
+        index += 1;
+    }
+};
+
+/// One row of `LedState::TRANSITIONS`: where each kind of press leads from `state`.
+struct Transition {
+    state: LedState,
+    /// After a short press on `button0`.
+    next: LedState,
+    /// After a short press on `button1` or a long press on either button.
+    previous: LedState,
+    /// After a very long press on `button0`. (One on `button1` is the blackout, see
+    /// `LedState::BLACKOUT`.)
+    very_long: LedState,
+}
+
+impl Transition {
+    const fn new(state: LedState, next: LedState, previous: LedState, very_long: LedState) -> Self {
+        Self {
+            state,
+            next,
+            previous,
+            very_long,
         }
     }
 }
@@ -1,21 +1,74 @@
 //! Share the types and modules defined below across the crate.
+//!
+//! Logs go through `defmt`, each prefixed with its subsystem (`button:`, `led:`, `state:` and so
+//! on). State transitions, power changes and failures log at `info` or `warn`. Per-press and
+//! per-schedule details log at `debug`, so setting `DEFMT_LOG = "info"` in
+//! `.cargo/config.toml` compiles them out.
 #![no_std]
 #![no_main]
 
+mod app_config;
 mod button;
+mod buzzer;
 mod error;
+#[cfg(feature = "event-log")]
+mod event_log;
+#[cfg(feature = "fault-blink")]
+mod fault_blink;
+mod hal;
 mod hardware;
 mod led;
+mod led_chase;
 mod led_state;
+mod light_sensor;
+#[cfg(feature = "mock")]
+mod mock_led;
+#[cfg(feature = "mock")]
+mod mock_pin;
+mod mode_table;
+mod morse_message;
 mod never;
-mod press_duration;
+mod potentiometer;
+mod power;
+mod pwm_slice;
+mod rgb_led;
+mod rng;
 mod schedule;
 pub mod shared_const;
+mod state_machine;
+mod state_store;
+mod usb;
+mod watchdog;
 
-pub use button::{Button, PressDuration};
-pub use error::Result;
-pub use hardware::Hardware;
-pub use led::{Led, LedNotifier};
-pub use led_state::LedState;
+pub use app_config::AppConfig;
+pub use button::{
+    press_or_chord, Button, ButtonConfig, ButtonEvent, ButtonPolarity, PairPress, PressDuration,
+    PressEvent, PressPattern,
+};
+pub use buzzer::Buzzer;
+pub use error::{Error, Result};
+#[cfg(feature = "event-log")]
+pub use event_log::dump_log;
+pub use hal::{HalInput, InputPin, LedOutput};
+pub use hardware::{Hardware, HardwareConfig};
+pub use led::{device_loop, Led, LedChannel, LedCommand, LedNotifier, LedPair, LedPin, Leds};
+pub use led_chase::LedChase;
+pub use led_state::{LedContext, LedState};
+pub use light_sensor::LightSensor;
+#[cfg(feature = "mock")]
+pub use mock_led::{MockLed, MockToggle};
+#[cfg(feature = "mock")]
+pub use mock_pin::MockPin;
+pub use mode_table::{Mode, ModeTable, Pattern};
+pub use morse_message::MorseMessage;
 pub use never::Never;
-pub use schedule::Schedule;
+pub use potentiometer::{Potentiometer, SharedAdc};
+pub use power::Power;
+pub use pwm_slice::{PwmChannel, PwmSlice};
+pub use rgb_led::{Color, RgbLed};
+pub use rng::Rng;
+pub use schedule::{Schedule, ScheduleBuilder};
+pub use state_machine::StateMachine;
+pub use state_store::{StateFlash, StateStore};
+pub use usb::{SerialCommand, SerialCommands, SerialConsole};
+pub use watchdog::Watchdog;
@@ -0,0 +1,93 @@
+use embassy_executor::{SpawnError, Spawner};
+use embassy_rp::adc::Channel;
+use embassy_time::Timer;
+
+use crate::{
+    error::Result,
+    potentiometer::SharedAdc,
+    shared_const::{
+        DARK_THRESHOLD, FADE_STEP_INTERVAL, FULL_BRIGHTNESS, LIGHT_HYSTERESIS,
+        LIGHT_SAMPLE_INTERVAL, NIGHT_BRIGHTNESS, NIGHT_FADE_DURATION,
+    },
+    Led,
+};
+
+/// A photoresistor wired as a voltage divider to one of the RP2040's ADC pins, with the
+/// photoresistor to 3.3 V and a fixed resistor to ground, so that more light reads higher.
+///
+/// It shares the ADC with the `Potentiometer`.
+pub struct LightSensor {
+    adc: &'static SharedAdc,
+    channel: Channel<'static>,
+}
+
+impl LightSensor {
+    /// Creates a new `LightSensor` that reads `channel` with the shared `adc`.
+    #[must_use]
+    pub const fn new(adc: &'static SharedAdc, channel: Channel<'static>) -> Self {
+        Self { adc, channel }
+    }
+
+    /// Reads the ambient light, from 0 (dark) to `POTENTIOMETER_FULL_SCALE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ADC conversion fails.
+    pub async fn read(&mut self) -> Result<u16> {
+        Ok(self.adc.lock().await.read(&mut self.channel).await?)
+    }
+
+    /// Starts a task that dims `leds` at night: it reads the light every
+    /// `LIGHT_SAMPLE_INTERVAL`, and once a reading falls below `DARK_THRESHOLD`, fades the LEDs
+    /// to `NIGHT_BRIGHTNESS` over `NIGHT_FADE_DURATION`. They fade back to full brightness once a
+    /// reading rises above `DARK_THRESHOLD + LIGHT_HYSTERESIS`. Readings in between change
+    /// nothing, so a light level hovering at the threshold doesn't make the LEDs flicker.
+    ///
+    /// The schedules keep running, only dimmer, as the LEDs are driven by PWM. (On an
+    /// `LedPin::Digital` pin, any nonzero brightness is fully on, so this does nothing there.)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpawnError` if the task cannot be spawned.
+    pub fn dim_when_dark(
+        self,
+        leds: [Led<'static>; 2],
+        spawner: Spawner,
+    ) -> Result<(), SpawnError> {
+        spawner.spawn(light_task(self, leds))
+    }
+}
+
+#[embassy_executor::task]
+async fn light_task(mut light_sensor: LightSensor, mut leds: [Led<'static>; 2]) -> ! {
+    let mut dark = false;
+    loop {
+        match light_sensor.read().await {
+            Ok(reading) => {
+                let now_dark = if dark {
+                    reading <= DARK_THRESHOLD.saturating_add(LIGHT_HYSTERESIS)
+                } else {
+                    reading < DARK_THRESHOLD
+                };
+                if now_dark != dark {
+                    dark = now_dark;
+                    defmt::info!("light: reading {}, dark = {}", reading, dark);
+                    let target = if dark {
+                        NIGHT_BRIGHTNESS
+                    } else {
+                        FULL_BRIGHTNESS
+                    };
+                    for led in &mut leds {
+                        // A fade would replace a schedule the `Led` hasn't picked up yet.
+                        while led.has_pending_command() {
+                            Timer::after(FADE_STEP_INTERVAL).await;
+                        }
+                        led.fade_to(target, NIGHT_FADE_DURATION);
+                    }
+                }
+            },
+            Err(err) => defmt::warn!("light: read failed: {}", err),
+        }
+        Timer::after(LIGHT_SAMPLE_INTERVAL).await;
+    }
+}
@@ -7,7 +7,14 @@
 
 use defmt_rtt as _;
 use embassy_executor::Spawner;
-use lib::{Button, Led, LedNotifier, LedState, Never, Result};
+use embassy_futures::select::{select, Either};
+use embassy_rp::gpio::Level;
+use lib::{
+    shared_const::BUTTON_STUCK_WINDOW, AppConfig, Button, Led, LedContext, LedNotifier, LedPair,
+    LedState, MorseMessage, Never, PressDuration, Result, Schedule, SerialCommand, SerialCommands,
+    SerialConsole, StateMachine, StateStore,
+};
+#[cfg(not(feature = "fault-blink"))]
 use panic_probe as _;
 
 // In bare-metal development, your application is launched by the processor's boot loader (from ROM).
@@ -17,7 +24,19 @@ use panic_probe as _;
 #[embassy_executor::main]
 async fn main(spawner: Spawner) -> ! {
     // If it returns, something went wrong.
-    let err = inner_main(spawner).await.unwrap_err();
+    halt(inner_main(spawner).await)
+}
+
+/// Reports why `inner_main` returned and stops.
+///
+/// The error is logged before panicking, so it shows up in the defmt log even when the panic
+/// handler's own report is lost or truncated.
+fn halt(result: Result<Never>) -> ! {
+    let err = match result {
+        Ok(never) => never.unreachable(),
+        Err(err) => err,
+    };
+    defmt::error!("Fatal error: {}", err);
     panic!("{err}");
 }
 
@@ -30,20 +49,89 @@ async fn main(spawner: Spawner) -> ! {
 #[expect(clippy::items_after_statements, reason = "Keeps related code together")]
 async fn inner_main(spawner: Spawner) -> Result<Never> {
     // Initialize the hardware.
-    let hardware: lib::Hardware<'_> = lib::Hardware::default();
+    let hardware: lib::Hardware<'_> = lib::Hardware::take()?;
+    // The tunable settings. Loading them from flash would go here; for now, the defaults.
+    let config = AppConfig::default();
 
     // Start abstract peripherals.
     static LED_NOTIFIER0: LedNotifier = Led::notifier();
-    let mut led0 = Led::new(hardware.led0, &LED_NOTIFIER0, spawner)?;
+    let led0 = Led::new(hardware.led0, &LED_NOTIFIER0, spawner)?;
+    // `led1` runs on the second core, to demonstrate a cross-core `Led`.
     static LED_NOTIFIER1: LedNotifier = Led::notifier();
-    let mut led1 = Led::new(hardware.led1, &LED_NOTIFIER1, spawner)?;
-    let mut button = Button::new(hardware.button);
+    let led1 = Led::new_on_core1(hardware.led1, &LED_NOTIFIER1, hardware.core1);
+    // The on-board LED blips once a second, whatever the state, to show the firmware is alive.
+    static LED_NOTIFIER_ONBOARD: LedNotifier = Led::notifier();
+    let mut led_onboard = Led::new(hardware.led_onboard, &LED_NOTIFIER_ONBOARD, spawner)?;
+    led_onboard.schedule(Schedule::heartbeat()?);
+    // Turning the potentiometer speeds up or slows down both LEDs.
+    let speed_leds = [Led::from_notifier(&LED_NOTIFIER0), Led::from_notifier(&LED_NOTIFIER1)];
+    hardware.potentiometer.control_speed(speed_leds, spawner)?;
+    // A dark room dims both LEDs.
+    let night_leds = [Led::from_notifier(&LED_NOTIFIER0), Led::from_notifier(&LED_NOTIFIER1)];
+    hardware.light_sensor.dim_when_dark(night_leds, spawner)?;
+    let mut button0 = Button::with_config(hardware.button0, config.button);
+    let mut button1 = Button::with_config(hardware.button1, config.button);
+    // A stuck button would be misread as an endless press, so stop instead (which the
+    // `fault-blink` feature shows on the on-board LED).
+    button0.check_stuck(BUTTON_STUCK_WINDOW).await?;
+    button1.check_stuck(BUTTON_STUCK_WINDOW).await?;
+
+    let mut state_store = StateStore::new(hardware.flash);
+
+    // The messages flashed in the `AllMorse` and `DualMorse` states.
+    static MORSE_MESSAGE: MorseMessage = MorseMessage::new();
+    MORSE_MESSAGE.set("HELLO")?;
+    static MORSE_MESSAGE1: MorseMessage = MorseMessage::new();
+    MORSE_MESSAGE1.set("OK")?;
+
+    // Commands typed on the USB serial console.
+    static SERIAL_COMMANDS: SerialCommands = SerialConsole::commands();
+    let console = SerialConsole::new(hardware.usb, &SERIAL_COMMANDS, spawner)?;
 
-    // Run the state machine.
-    let mut state = LedState::default();
+    // Reset the device if the main loop stalls. The waits for input below are wrapped in
+    // `feed_while()`, because a state can legitimately wait on a button for hours.
+    let mut watchdog = hardware.watchdog;
+    watchdog.start(config.watchdog_timeout);
+
+    // Run the state machine, resuming from the state saved before the last power cycle.
+    let mut context = LedContext {
+        leds: LedPair::new(led0, led1),
+        button0,
+        button1,
+        messages: [&MORSE_MESSAGE, &MORSE_MESSAGE1],
+        buzzer: hardware.buzzer,
+        config,
+    };
+    let mut state = LedState::load(&mut state_store);
     loop {
-        defmt::info!("State: {:?}", state);
-        state = state.execute(&mut led0, &mut led1, &mut button).await?;
+        watchdog.feed();
+        defmt::info!("state: running {}", state);
+        let outcome =
+            watchdog.feed_while(select(state.step(&mut context), console.receive())).await;
+        // A console command drops `step` wherever it was, which in `Sos` may be mid-tone.
+        context.buzzer.set_level(Level::Low);
+        match outcome {
+            Either::First(next_state) => state = next_state?,
+            Either::Second(SerialCommand::State(next_state)) => state = next_state,
+            // Re-running the state restarts its schedules at the new speed.
+            Either::Second(SerialCommand::TimeScale(permille)) => {
+                context.leds.set_time_scale(permille);
+            },
+            // Show the custom schedule until a press, then resume the state.
+            Either::Second(SerialCommand::Schedule(schedule)) => {
+                context.leds.schedule_together(schedule);
+                let press = wait_for_press(&mut context.button0, &mut context.button1);
+                watchdog.feed_while(press).await;
+            },
+        }
+        state.save(&mut state_store)?;
+    }
+}
+
+/// Waits for a press of either button, of any duration.
+async fn wait_for_press(button0: &mut Button, button1: &mut Button) -> PressDuration {
+    match select(button0.press_duration(), button1.press_duration()).await {
+        Either::First(press_duration) | Either::Second(press_duration) => press_duration,
     }
 }
 
@@ -0,0 +1,97 @@
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::Instant;
+use heapless::{HistoryBuffer, Vec};
+
+use crate::{shared_const::MOCK_LED_LOG_CAPACITY, LedOutput};
+
+/// An `LedOutput` that lights nothing but records when its brightness changes, for checking a
+/// schedule's timing.
+///
+/// A `MockLed` should be assigned to a static variable, and `device_loop` given a reference to
+/// it. Each change is recorded with the `Instant` it happened, so a script can compare the
+/// gaps between toggles against the schedule it sent. Only the last `MOCK_LED_LOG_CAPACITY`
+/// changes are kept.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static MOCK_NOTIFIER: LedNotifier = Led::notifier();
+/// static MOCK_LED: MockLed = MockLed::new();
+///
+/// #[embassy_executor::task]
+/// async fn mock_led_task() -> ! {
+///     device_loop(&MOCK_LED, &MOCK_NOTIFIER).await
+/// }
+///
+/// spawner.spawn(mock_led_task())?;
+/// let mut led = Led::from_notifier(&MOCK_NOTIFIER);
+/// led.schedule(Schedule::fast_no_delay());
+/// Timer::after(4 * FAST_FLASH_DELAY).await;
+/// let toggles = MOCK_LED.toggles();
+/// for (earlier, later) in toggles.iter().zip(toggles.iter().skip(1)) {
+///     assert_eq!(later.at - earlier.at, FAST_FLASH_DELAY);
+/// }
+/// ```
+pub struct MockLed {
+    toggles:
+        Mutex<CriticalSectionRawMutex, RefCell<HistoryBuffer<MockToggle, MOCK_LED_LOG_CAPACITY>>>,
+}
+
+/// One recorded change of a `MockLed`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub struct MockToggle {
+    /// When the change happened.
+    pub at: Instant,
+    /// The brightness it changed to, in percent, where 0 is off. (An inverted pin records the
+    /// LED's brightness, not the pin's duty cycle.)
+    pub brightness: u8,
+}
+
+impl MockLed {
+    /// Creates a new `MockLed` with nothing recorded.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            toggles: Mutex::new(RefCell::new(HistoryBuffer::new())),
+        }
+    }
+
+    /// Returns the recorded changes, oldest first.
+    #[must_use]
+    pub fn toggles(&self) -> Vec<MockToggle, MOCK_LED_LOG_CAPACITY> {
+        self.toggles.lock(|cell| cell.borrow().oldest_ordered().copied().collect())
+    }
+
+    /// Forgets the recorded changes, e.g. before sending the schedule under test.
+    pub fn clear(&self) {
+        self.toggles.lock(|cell| cell.borrow_mut().clear());
+    }
+}
+
+impl Default for MockLed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LedOutput for &MockLed {
+    /// Records `brightness` if it differs from the last one recorded.
+    fn set(&mut self, brightness: u8, _invert: bool) {
+        self.toggles.lock(|cell| {
+            let mut toggles = cell.borrow_mut();
+            if toggles.recent().map(|toggle| toggle.brightness) != Some(brightness) {
+                toggles.write(MockToggle {
+                    at: Instant::now(),
+                    brightness,
+                });
+            }
+        });
+    }
+
+    /// A `MockLed` records any brightness, so fades are recorded step by step, not dithered.
+    fn is_digital(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,101 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_rp::gpio::{DormantWake, Level};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+
+use crate::InputPin;
+
+/// An input pin whose level is set in software, for scripting button presses.
+///
+/// A `MockPin` should be assigned to a static variable. A `Button` is created from a
+/// reference to it, while a script (e.g. another task) presses and releases the button with
+/// `set_high()` and `set_low()`. Only one task should wait on a `MockPin` at a time.
+pub struct MockPin {
+    level: AtomicBool,
+    changed: Signal<CriticalSectionRawMutex, ()>,
+}
+
+impl MockPin {
+    /// Creates a new `MockPin` that starts low.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            level: AtomicBool::new(false),
+            changed: Signal::new(),
+        }
+    }
+
+    /// Drives the pin high.
+    pub fn set_high(&self) {
+        self.set(true);
+    }
+
+    /// Drives the pin low.
+    pub fn set_low(&self) {
+        self.set(false);
+    }
+
+    /// Inverts the pin's level.
+    pub fn toggle(&self) {
+        self.set(!self.level.load(Ordering::Relaxed));
+    }
+
+    fn set(&self, level: bool) {
+        self.level.store(level, Ordering::Relaxed);
+        self.changed.signal(());
+    }
+
+    async fn wait_for_level(&self, level: bool) {
+        while self.level.load(Ordering::Relaxed) != level {
+            self.changed.wait().await;
+        }
+    }
+
+    async fn wait_for_edge(&self, level: bool) {
+        // Forget any change from before the call, so that only a new edge counts.
+        self.changed.reset();
+        loop {
+            self.changed.wait().await;
+            if self.level.load(Ordering::Relaxed) == level {
+                return;
+            }
+        }
+    }
+}
+
+impl Default for MockPin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputPin for &MockPin {
+    fn is_high(&self) -> bool {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    fn is_low(&self) -> bool {
+        !self.level.load(Ordering::Relaxed)
+    }
+
+    async fn wait_for_high(&mut self) {
+        self.wait_for_level(true).await;
+    }
+
+    async fn wait_for_low(&mut self) {
+        self.wait_for_level(false).await;
+    }
+
+    async fn wait_for_rising_edge(&mut self) {
+        self.wait_for_edge(true).await;
+    }
+
+    async fn wait_for_falling_edge(&mut self) {
+        self.wait_for_edge(false).await;
+    }
+
+    /// A `MockPin` isn't wired to the chip, so it can't wake it.
+    fn dormant_wake(&mut self, _level: Level) -> Option<DormantWake<'_>> {
+        None
+    }
+}
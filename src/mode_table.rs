@@ -0,0 +1,193 @@
+use embassy_time::Duration;
+
+use crate::{
+    error::Result, shared_const::SOS_ARMED_FLASH, AppConfig, LedState, MorseMessage, Schedule,
+};
+
+/// What each `LedState` shows on the two LEDs, as data rather than code, so that a mode can be
+/// redefined by editing a table.
+///
+/// `ModeTable::default()` gives the built-in modes. `LedState::execute` looks the state up in
+/// `AppConfig::modes`. Only the output is table-driven: the transitions between states, and
+/// `Sos`'s buzzer, stay as they are. In `SpeedCycle`, the mode's schedules are also scaled by the
+/// current level.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let config = AppConfig {
+///     modes: ModeTable::default()
+///         .with(LedState::AlwaysOn, Mode::Both(Pattern::Flash {
+///             on: Duration::from_millis(900),
+///             off: Duration::from_millis(100),
+///         })),
+///     ..AppConfig::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, defmt::Format)]
+pub struct ModeTable {
+    /// The mode of each state, at the state's `as_index()`.
+    modes: [Mode; LedState::all().len()],
+}
+
+/// How one state lights the two LEDs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum Mode {
+    /// The same pattern on both LEDs, in step.
+    Both(Pattern),
+    /// The same pattern on both LEDs, `led0` half a cycle behind `led1`, so that they take turns.
+    Alternate(Pattern),
+    /// A pattern for `led0` and another for `led1`.
+    ///
+    /// A pattern that fails to build (such as a message that can't be encoded) leaves only its
+    /// own LED off, with a warning, while the other LED still shows its pattern.
+    Each(Pattern, Pattern),
+}
+
+/// A recipe for one LED's `Schedule`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum Pattern {
+    /// On and off at `AppConfig::fast_flash_delay` (`Schedule::fast_no_delay_with`).
+    FastFlash,
+    /// Like `FastFlash`, after one delay off (`Schedule::fast_with_delay_with`).
+    FastFlashDelayed,
+    /// On and off at `AppConfig::slow_flash_delay` (`Schedule::slow_no_delay_with`).
+    SlowFlash,
+    /// On for `on`, then off for `off`.
+    Flash {
+        /// How long the LED is on.
+        on: Duration,
+        /// How long the LED is off.
+        off: Duration,
+    },
+    /// The breathing ramp (`Schedule::breathing`).
+    Breathing,
+    /// SOS with 120-millisecond dots (`Schedule::sos_slow`).
+    SosSlow,
+    /// SOS with 60-millisecond dots (`Schedule::sos_fast`).
+    SosFast,
+    /// Always on.
+    On,
+    /// Always off.
+    Off,
+    /// The first of the `MorseMessage`s passed to `LedState::execute`.
+    FirstMessage,
+    /// The second of the `MorseMessage`s passed to `LedState::execute`.
+    SecondMessage,
+}
+
+impl ModeTable {
+    /// Returns the mode of `state`.
+    #[must_use]
+    pub fn mode(&self, state: LedState) -> Mode {
+        self.modes.get(usize::from(state.as_index())).copied().unwrap_or(Mode::Both(Pattern::Off))
+    }
+
+    /// Makes `state` show `mode`.
+    pub fn set(&mut self, state: LedState, mode: Mode) {
+        if let Some(slot) = self.modes.get_mut(usize::from(state.as_index())) {
+            *slot = mode;
+        }
+    }
+
+    /// Returns this table with `state` showing `mode`, for building a table in one expression.
+    #[must_use]
+    pub fn with(mut self, state: LedState, mode: Mode) -> Self {
+        self.set(state, mode);
+        self
+    }
+}
+
+impl Default for ModeTable {
+    fn default() -> Self {
+        let mut table = Self {
+            modes: [Mode::Both(Pattern::Off); LedState::all().len()],
+        };
+        for &state in LedState::all() {
+            table.set(state, built_in(state));
+        }
+        table
+    }
+}
+
+/// The built-in mode of `state`.
+const fn built_in(state: LedState) -> Mode {
+    match state {
+        LedState::FastAlternate => Mode::Alternate(Pattern::FastFlash),
+        LedState::FastTogether => Mode::Both(Pattern::FastFlashDelayed),
+        LedState::SlowAlternate | LedState::SpeedCycle { .. } => {
+            Mode::Alternate(Pattern::SlowFlash)
+        },
+        LedState::Breathing => Mode::Both(Pattern::Breathing),
+        LedState::Sos => Mode::Each(Pattern::SosSlow, Pattern::SosFast),
+        LedState::AlwaysOn => Mode::Both(Pattern::On),
+        LedState::AlwaysOff => Mode::Both(Pattern::Off),
+        LedState::AllMorse => Mode::Both(Pattern::FirstMessage),
+        LedState::DualMorse => Mode::Each(Pattern::FirstMessage, Pattern::SecondMessage),
+        LedState::SosArmed { .. } => Mode::Both(Pattern::Flash {
+            on: SOS_ARMED_FLASH,
+            off: SOS_ARMED_FLASH,
+        }),
+    }
+}
+
+impl Mode {
+    /// Returns the schedules for `led0` and `led1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `Both` or `Alternate` pattern fails to build.
+    pub fn schedules(
+        self,
+        messages: [&MorseMessage; 2],
+        config: &AppConfig,
+    ) -> Result<(Schedule, Schedule)> {
+        Ok(match self {
+            Self::Both(pattern) => {
+                let schedule = pattern.schedule(messages, config)?;
+                (schedule.clone(), schedule)
+            },
+            Self::Alternate(pattern) => {
+                let schedule = pattern.schedule(messages, config)?;
+                (schedule.half_cycle_later()?, schedule)
+            },
+            Self::Each(pattern0, pattern1) => (
+                pattern0.schedule_or_off(messages, config),
+                pattern1.schedule_or_off(messages, config),
+            ),
+        })
+    }
+}
+
+impl Pattern {
+    /// Builds the schedule.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern's durations are invalid (e.g. a zero flash delay) or a
+    /// message can't be encoded.
+    pub fn schedule(self, messages: [&MorseMessage; 2], config: &AppConfig) -> Result<Schedule> {
+        let [first_message, second_message] = messages;
+        match self {
+            Self::FastFlash => Schedule::fast_no_delay_with(config),
+            Self::FastFlashDelayed => Schedule::fast_with_delay_with(config),
+            Self::SlowFlash => Schedule::slow_no_delay_with(config),
+            Self::Flash { on, off } => Schedule::builder().on(on)?.off(off)?.build(),
+            Self::Breathing => Schedule::breathing(),
+            Self::SosSlow => Schedule::sos_slow(),
+            Self::SosFast => Schedule::sos_fast(),
+            Self::On => Ok(Schedule::on()),
+            Self::Off => Ok(Schedule::off()),
+            Self::FirstMessage => first_message.schedule(),
+            Self::SecondMessage => second_message.schedule(),
+        }
+    }
+
+    /// Like `schedule`, but an LED whose pattern fails is left off, with a warning.
+    fn schedule_or_off(self, messages: [&MorseMessage; 2], config: &AppConfig) -> Schedule {
+        self.schedule(messages, config).unwrap_or_else(|err| {
+            defmt::warn!("state: pattern {} left off: {}", self, err);
+            Schedule::off()
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use heapless::String;
+
+use crate::{
+    error::{Error, Result},
+    shared_const::{MORSE_MESSAGE_CAPACITY, MORSE_MESSAGE_MILLIS_PER_DOT},
+    Schedule,
+};
+
+/// A message that the `LedState::AllMorse` or `LedState::DualMorse` state flashes.
+///
+/// A `MorseMessage` should be assigned to a static variable and passed to `LedState::execute()`.
+/// Anyone holding a reference can change it with `set()`; the new message is shown the next time
+/// the state machine enters `AllMorse`.
+pub struct MorseMessage(Mutex<CriticalSectionRawMutex, RefCell<String<MORSE_MESSAGE_CAPACITY>>>);
+
+impl MorseMessage {
+    /// Creates a new, empty `MorseMessage`. An empty message keeps the LEDs off.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(String::new())))
+    }
+
+    /// Replaces the message with `text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the message unchanged, if `text` is longer than
+    /// `MORSE_MESSAGE_CAPACITY` bytes or can't be encoded by `Schedule::from_morse()`.
+    pub fn set(&self, text: &str) -> Result<()> {
+        Self::encode(text)?;
+        let message = String::try_from(text).map_err(|()| Error::MorseMessageTooLong)?;
+        self.0.lock(|cell| cell.replace(message));
+        Ok(())
+    }
+
+    /// Returns a schedule that flashes the current message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message can't be encoded, which `set()` rules out.
+    pub fn schedule(&self) -> Result<Schedule> {
+        self.0.lock(|cell| Self::encode(&cell.borrow()))
+    }
+
+    fn encode(text: &str) -> Result<Schedule> {
+        Schedule::from_morse(text, MORSE_MESSAGE_MILLIS_PER_DOT)
+    }
+}
+
+impl Default for MorseMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,43 @@
+use core::convert::Infallible;
+
 /// Rust's `!` is unstable.  This is a locally-defined equivalent which is stable.
+///
+/// A `Never` can't be built, so code holding one can't be reached. `match never {}` is
+/// exhaustive, and `Never::unreachable` turns one into the real `!`. It converts both ways with
+/// `Infallible`, the standard library's own stand-in, for libraries that use that instead.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fn halt(result: Result<Never>) -> ! {
+///     match result {
+///         Ok(never) => match never {},
+///         Err(err) => panic!("{err}"),
+///     }
+/// }
+///
+/// fn bridge(result: Result<Never>) -> Result<Infallible> {
+///     result.map(Infallible::from)
+/// }
+/// ```
 #[derive(Debug)]
 pub enum Never {}
+
+impl Never {
+    /// Returns `!`, which code holding a `Never` can never actually do.
+    pub const fn unreachable(self) -> ! {
+        match self {}
+    }
+}
+
+impl From<Never> for Infallible {
+    fn from(never: Never) -> Self {
+        never.unreachable()
+    }
+}
+
+impl From<Infallible> for Never {
+    fn from(infallible: Infallible) -> Self {
+        match infallible {}
+    }
+}
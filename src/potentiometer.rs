@@ -0,0 +1,93 @@
+use embassy_executor::{SpawnError, Spawner};
+use embassy_rp::adc::{Adc, Async, Channel};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use embassy_time::Timer;
+
+use crate::{
+    error::Result,
+    shared_const::{
+        FASTEST_TIME_SCALE_PERMILLE, POTENTIOMETER_FULL_SCALE, POTENTIOMETER_SAMPLE_INTERVAL,
+        SLOWEST_TIME_SCALE_PERMILLE, TIME_SCALE_STEP_PERMILLE,
+    },
+    Led,
+};
+
+/// Type alias for the RP2040's one ADC, shared by the `Potentiometer` and the `LightSensor`,
+/// each of which holds it only for the length of a conversion.
+pub type SharedAdc = Mutex<CriticalSectionRawMutex, Adc<'static, Async>>;
+
+/// A potentiometer (knob) wired as a voltage divider to one of the RP2040's ADC pins.
+pub struct Potentiometer {
+    adc: &'static SharedAdc,
+    channel: Channel<'static>,
+}
+
+impl Potentiometer {
+    /// Creates a new `Potentiometer` that reads `channel` with the shared `adc`.
+    #[must_use]
+    pub const fn new(adc: &'static SharedAdc, channel: Channel<'static>) -> Self {
+        Self { adc, channel }
+    }
+
+    /// Reads the knob's position, from 0 to `POTENTIOMETER_FULL_SCALE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ADC conversion fails.
+    pub async fn read(&mut self) -> Result<u16> {
+        Ok(self.adc.lock().await.read(&mut self.channel).await?)
+    }
+
+    /// Reads the knob's position as an `Led` time scale, in per mille.
+    ///
+    /// Fully counter-clockwise gives `SLOWEST_TIME_SCALE_PERMILLE` and fully clockwise gives
+    /// `FASTEST_TIME_SCALE_PERMILLE`, in steps of `TIME_SCALE_STEP_PERMILLE` so that ADC noise
+    /// doesn't keep changing the scale.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ADC conversion fails.
+    pub async fn time_scale(&mut self) -> Result<u32> {
+        let reading = u32::from(self.read().await?).min(POTENTIOMETER_FULL_SCALE);
+        let range = SLOWEST_TIME_SCALE_PERMILLE.saturating_sub(FASTEST_TIME_SCALE_PERMILLE);
+        let offset = range
+            .saturating_mul(reading)
+            .checked_div(POTENTIOMETER_FULL_SCALE)
+            .and_then(|offset| offset.checked_div(TIME_SCALE_STEP_PERMILLE))
+            .map_or(0, |steps| steps.saturating_mul(TIME_SCALE_STEP_PERMILLE));
+        Ok(SLOWEST_TIME_SCALE_PERMILLE.saturating_sub(offset))
+    }
+
+    /// Starts a task that samples the knob every `POTENTIOMETER_SAMPLE_INTERVAL` and sets the
+    /// time scale of `leds` whenever it changes, so turning the knob speeds up or slows down
+    /// whatever they are showing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpawnError` if the task cannot be spawned.
+    pub fn control_speed(
+        self,
+        leds: [Led<'static>; 2],
+        spawner: Spawner,
+    ) -> Result<(), SpawnError> {
+        spawner.spawn(speed_task(self, leds))
+    }
+}
+
+#[embassy_executor::task]
+async fn speed_task(mut potentiometer: Potentiometer, mut leds: [Led<'static>; 2]) -> ! {
+    let mut current = None;
+    loop {
+        match potentiometer.time_scale().await {
+            Ok(permille) if current != Some(permille) => {
+                current = Some(permille);
+                for led in &mut leds {
+                    led.set_time_scale(permille);
+                }
+            },
+            Ok(_) => {},
+            Err(err) => defmt::warn!("knob: read failed: {}", err),
+        }
+        Timer::after(POTENTIOMETER_SAMPLE_INTERVAL).await;
+    }
+}
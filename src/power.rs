@@ -0,0 +1,65 @@
+use core::num::NonZeroU32;
+
+use embassy_time::Timer;
+
+use crate::{
+    shared_const::{DORMANT_SETTLE_DELAY, SLEEP_BLINKS, SLEEP_BLINKS_DURATION, ZERO_DELAY},
+    Button, InputPin, LedPair, Schedule,
+};
+
+/// Puts the RP2040 into its lowest-power (`DORMANT`) state while nothing needs to run.
+///
+/// `DORMANT` stops every clock, so while it lasts nothing else runs either: the other core, the
+/// LEDs, the heartbeat, the serial console and the timers all freeze where they are (USB
+/// disconnects). That is worth it only when the device is meant to look off, as in
+/// `LedState::AlwaysOff`.
+pub struct Power;
+
+impl Power {
+    /// Shows the user that the device is going to sleep: both LEDs blink `SLEEP_BLINKS` once, a
+    /// long blink and then a short one, and are then stopped, off. Call this before
+    /// `sleep_until_pressed`, so that the device doesn't just freeze whatever it was showing.
+    ///
+    /// This takes `SLEEP_BLINKS_DURATION` (600 ms) and holds up the caller, i.e. the main loop,
+    /// for all of it on purpose: the chip shouldn't sleep until the blinks are over. The blinks
+    /// follow the LEDs' time scale like any schedule, but the wait doesn't, so at a slowed-down
+    /// scale the stop cuts them short, and the LEDs are always off when this returns.
+    pub async fn prepare_sleep(leds: &mut LedPair<'_>) {
+        let mut blinks = Schedule::new_const(ZERO_DELAY, SLEEP_BLINKS);
+        blinks.repeat = Some(NonZeroU32::MIN);
+        defmt::debug!("power: going to sleep");
+        leds.led0.restart(blinks.clone());
+        leds.led1.restart(blinks);
+        Timer::after(SLEEP_BLINKS_DURATION).await;
+        leds.led0.stop();
+        leds.led1.stop();
+    }
+
+    /// Sleeps in `DORMANT` until either button is pressed, then returns `true`.
+    ///
+    /// This first waits `DORMANT_SETTLE_DELAY`, so that the LEDs can apply whatever was just
+    /// scheduled on them. The press that wakes the chip only wakes it; it is not seen by
+    /// `Button::press_duration` and friends, which only wait for the next one.
+    ///
+    /// If either button can't wake the chip (e.g. it is on a `MockPin`) or is already held, this
+    /// returns `false` without sleeping. The executor still sleeps between interrupts as usual.
+    pub async fn sleep_until_pressed<P0: InputPin, P1: InputPin>(
+        button0: &mut Button<P0>,
+        button1: &mut Button<P1>,
+    ) -> bool {
+        Timer::after(DORMANT_SETTLE_DELAY).await;
+        if button0.is_down() || button1.is_down() {
+            return false;
+        }
+        // Each guard disarms its pin's wake when dropped, i.e. after the chip wakes.
+        match (button0.dormant_wake(), button1.dormant_wake()) {
+            (Some(_wake0), Some(_wake1)) => {
+                defmt::info!("power: entering DORMANT until a button is pressed");
+                embassy_rp::clocks::dormant_sleep();
+                defmt::info!("power: woke from DORMANT");
+                true
+            },
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use core::cell::RefCell;
+
+use embassy_rp::pwm::{Config, Pwm};
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+
+use crate::shared_const::{FULL_BRIGHTNESS, PWM_COUNTS_PER_PERCENT, PWM_TOP};
+
+/// A PWM slice whose two channels can be driven independently, e.g. by two `Led` tasks.
+///
+/// An RP2040 PWM slice owns both its A and B pins, but each channel has its own duty cycle. The
+/// slice is kept behind a critical-section mutex so that each `PwmChannel` can update its own
+/// compare value without disturbing the other.
+///
+/// A `PwmSlice` should be assigned to a static variable and then initialized once with
+/// `PwmSlice::init()`.
+pub struct PwmSlice(Mutex<CriticalSectionRawMutex, RefCell<Option<(Pwm<'static>, Config)>>>);
+
+impl PwmSlice {
+    /// Creates a new, uninitialized `PwmSlice`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Mutex::new(RefCell::new(None)))
+    }
+
+    /// Takes ownership of `pwm` (created with both its A and B outputs) and returns a handle to
+    /// each of its channels. Both channels start at 0% duty.
+    pub fn init(&'static self, mut pwm: Pwm<'static>) -> (PwmChannel, PwmChannel) {
+        let mut config = Config::default();
+        config.top = PWM_TOP;
+        pwm.set_config(&config);
+        self.0.lock(|cell| cell.replace(Some((pwm, config))));
+
+        let channel_a = PwmChannel {
+            slice: self,
+            channel: Channel::A,
+        };
+        let channel_b = PwmChannel {
+            slice: self,
+            channel: Channel::B,
+        };
+        (channel_a, channel_b)
+    }
+}
+
+impl Default for PwmSlice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[expect(clippy::min_ident_chars, reason = "Matches the RP2040 datasheet's channel names.")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+enum Channel {
+    A,
+    B,
+}
+
+/// One channel (and so one pin) of a shared `PwmSlice`.
+pub struct PwmChannel {
+    slice: &'static PwmSlice,
+    channel: Channel,
+}
+
+impl PwmChannel {
+    /// Sets the channel's duty cycle, where 0 is always low and 100 (or more) is always high.
+    pub fn set_duty_percent(&mut self, percent: u8) {
+        let compare =
+            u16::from(percent.min(FULL_BRIGHTNESS)).saturating_mul(PWM_COUNTS_PER_PERCENT);
+        self.slice.0.lock(|cell| {
+            if let Some((pwm, config)) = cell.borrow_mut().as_mut() {
+                match self.channel {
+                    Channel::A => config.compare_a = compare,
+                    Channel::B => config.compare_b = compare,
+                }
+                pwm.set_config(config);
+            }
+        });
+    }
+}
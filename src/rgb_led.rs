@@ -0,0 +1,130 @@
+use crate::{shared_const::FULL_BRIGHTNESS, Led, Schedule};
+
+/// A common-cathode RGB LED, built from three `Led`s, one per color channel.
+///
+/// Each channel is an ordinary `Led` with its own task, so the three pins can be any mix of
+/// `LedPin::Pwm` and `LedPin::Digital`. On PWM pins, `Color::Rgb` mixes any color. On digital
+/// pins any nonzero channel is fully on, so only the eight basic colors come out as named.
+/// For a common-anode RGB LED, make the notifiers with `LedNotifier::inverted`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static RGB_NOTIFIERS: [LedNotifier; 3] = [const { Led::notifier() }; 3];
+/// let [red, green, blue] = RGB_NOTIFIERS.each_ref();
+/// let mut rgb_led = RgbLed::new(
+///     Led::new(red_pin, red, spawner)?,
+///     Led::new(green_pin, green, spawner)?,
+///     Led::new(blue_pin, blue, spawner)?,
+/// );
+/// rgb_led.set_color(Color::Magenta);
+/// rgb_led.schedule(&Schedule::fast_no_delay());
+/// ```
+pub struct RgbLed {
+    red: Led<'static>,
+    green: Led<'static>,
+    blue: Led<'static>,
+    color: Color,
+}
+
+/// A color for an `RgbLed`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, defmt::Format)]
+pub enum Color {
+    /// All channels off.
+    #[default]
+    Black,
+    #[expect(missing_docs, reason = "The basic colors are self-explanatory.")]
+    Red,
+    #[expect(missing_docs, reason = "The basic colors are self-explanatory.")]
+    Green,
+    #[expect(missing_docs, reason = "The basic colors are self-explanatory.")]
+    Blue,
+    /// Red and green.
+    Yellow,
+    /// Green and blue.
+    Cyan,
+    /// Red and blue.
+    Magenta,
+    /// All channels fully on.
+    White,
+    /// Red, green and blue intensities, each from 0 (off) to 255 (fully on).
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Returns the red, green and blue intensities, each from 0 to 255.
+    #[must_use]
+    pub const fn components(self) -> (u8, u8, u8) {
+        match self {
+            Self::Black => (0, 0, 0),
+            Self::Red => (u8::MAX, 0, 0),
+            Self::Green => (0, u8::MAX, 0),
+            Self::Blue => (0, 0, u8::MAX),
+            Self::Yellow => (u8::MAX, u8::MAX, 0),
+            Self::Cyan => (0, u8::MAX, u8::MAX),
+            Self::Magenta => (u8::MAX, 0, u8::MAX),
+            Self::White => (u8::MAX, u8::MAX, u8::MAX),
+            Self::Rgb(red, green, blue) => (red, green, blue),
+        }
+    }
+}
+
+impl RgbLed {
+    /// Creates a new `RgbLed` from the `Led`s of its red, green and blue channels. It starts
+    /// `Color::Black`, i.e. off.
+    #[must_use]
+    pub fn new(red: Led<'static>, green: Led<'static>, blue: Led<'static>) -> Self {
+        let mut rgb_led = Self {
+            red,
+            green,
+            blue,
+            color: Color::Black,
+        };
+        rgb_led.schedule(&Schedule::off());
+        rgb_led
+    }
+
+    /// Lights the LED steadily in `color`.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.schedule(&Schedule::on());
+    }
+
+    /// Returns the color last set with `set_color()`.
+    #[must_use]
+    pub const fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Runs `schedule` in the current color, e.g. to blink it. The channels that the color
+    /// leaves dark stay off.
+    pub fn schedule(&mut self, schedule: &Schedule) {
+        let (red, green, blue) = self.color.components();
+        for (led, intensity) in
+            [(&mut self.red, red), (&mut self.green, green), (&mut self.blue, blue)]
+        {
+            let channel_schedule = if intensity == 0 {
+                Schedule::off()
+            } else {
+                schedule.clone()
+            };
+            led.schedule_at(channel_schedule, percent(intensity));
+        }
+    }
+}
+
+/// Converts a color intensity (0 to 255) to an `Led` brightness (0 to 100 percent), rounding to
+/// the nearest percent but keeping any nonzero intensity visible.
+fn percent(intensity: u8) -> u8 {
+    let scaled = u16::from(intensity)
+        .saturating_mul(u16::from(FULL_BRIGHTNESS))
+        .saturating_add(u16::from(u8::MAX).checked_div(2).unwrap_or(0))
+        .checked_div(u16::from(u8::MAX))
+        .and_then(|percent| u8::try_from(percent).ok())
+        .unwrap_or(FULL_BRIGHTNESS);
+    if intensity > 0 {
+        scaled.max(1)
+    } else {
+        0
+    }
+}
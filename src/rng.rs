@@ -0,0 +1,56 @@
+use embassy_rp::clocks::RoscRng;
+use rand_core::RngCore as _;
+
+/// A small, fast pseudo-random number generator for visual effects such as
+/// `Schedule::jittered`.
+///
+/// `Rng::new` seeds it from the RP2040's ring oscillator (ROSC), whose jitter differs from boot
+/// to boot, and then steps a xorshift generator, which is much faster than reading the ROSC one
+/// bit at a time. This is *not* cryptographic randomness: the ROSC bits are biased, the seed is
+/// only 32 bits, and anyone who sees a few outputs can predict the rest. If the ROSC is stopped
+/// (e.g. by custom clock settings) the seed is not random at all. Use `Rng::from_seed` for a
+/// repeatable sequence.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Creates a new `Rng` seeded from the ROSC.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_seed(RoscRng.next_u32())
+    }
+
+    /// Creates a new `Rng` that always produces the same sequence for the same `seed`.
+    #[must_use]
+    pub const fn from_seed(seed: u32) -> Self {
+        // Xorshift gets stuck at zero, so zero is nudged to another seed.
+        Self {
+            state: if seed == 0 { 0x9E37_79B9 } else { seed },
+        }
+    }
+
+    /// Returns the next pseudo-random `u32`.
+    pub const fn next_u32(&mut self) -> u32 {
+        // Marsaglia's xorshift32.
+        let mut state = self.state;
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        self.state = state;
+        state
+    }
+
+    /// Returns a pseudo-random number from 0 to `max`, inclusive.
+    pub fn up_to(&mut self, max: u64) -> u64 {
+        let wide = u64::from(self.next_u32()) << 32 | u64::from(self.next_u32());
+        max.checked_add(1).and_then(|span| wide.checked_rem(span)).unwrap_or(wide)
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,63 +1,138 @@
 use crate::{
     error::{Error, Result},
     shared_const::{
-        FAST_FLASH_DELAY, MORSE_DASH_MILLIS, MORSE_O_MILLIS, MORSE_S_MILLIS, ONE_DAY,
-        SCHEDULE_CAPACITY, SLOW_FLASH_DELAY, ZERO_DELAY,
+        morse_for, MorseSymbol, BREATHING_STEPS, FAST_BREATH_DELAY, FAST_FLASH_DELAY,
+        HEARTBEAT_BLIP, HEARTBEAT_PERIOD, MORSE_ALPHABET, MORSE_DASH_MILLIS,
+        MORSE_DECODE_SLACK_PERCENT, MORSE_DOT_MILLIS, MORSE_LETTER_GAP_MILLIS, MORSE_O_MILLIS,
+        MORSE_SYMBOL_CAPACITY, MORSE_S_MILLIS, MORSE_WORD_GAP_MILLIS, ONE_DAY, SCHEDULE_CAPACITY,
+        SLOW_BREATH_DELAY, SLOW_FLASH_DELAY, ZERO_DELAY,
     },
+    AppConfig, Rng,
 };
+use core::{fmt, num::NonZeroU32};
+
+use embassy_rp::gpio::Level;
 use embassy_time::Duration;
-use heapless::Vec;
+use heapless::{String, Vec};
 
 /// Represents a schedule for controlling an LED's on and off states.
 ///
 /// The schedule consists of an initial delay followed by a
 /// cycling `on_off_durations`.
 ///
-/// The `on_off_durations` must have an even number of elements.
-#[derive(Debug, Default)]
-pub struct Schedule {
-    /// The time the LED remains off before starting its on/off cycle.
+/// The `on_off_durations` must have an even number of elements, and every one but the last must
+/// be nonzero. A zero-length segment would have `device_loop` wake at once and toggle the LED
+/// faster than the eye can see; only a trailing `ZERO_DELAY` is allowed, as in `Schedule::on()`,
+/// where it ends the cycle without a visible gap.
+///
+/// `N` is the capacity of `on_off_durations`. It defaults to `SCHEDULE_CAPACITY`, which is
+/// enough for the built-in patterns; declare e.g. `Schedule<128>` for a long Morse message.
+///
+/// By default the cycle repeats forever. If `repeat` is set, the cycle runs that many times
+/// and the LED is then held off until a new schedule arrives.
+///
+/// The LED holds `start_level` through the `initial_delay`, and each segment of
+/// `on_off_durations` then takes the opposite level of the one before. With the default
+/// `Level::Low`, the LED waits off and the first segment is "on". With `Level::High`, the LED
+/// is lit from the moment the schedule starts, and the even segments (counting from zero) are
+/// "off" instead, so with no `initial_delay` the first segment is dark. An empty cycle holds
+/// `start_level` for good.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "WireSchedule<N>", try_from = "WireSchedule<N>")
+)]
+pub struct Schedule<const N: usize = SCHEDULE_CAPACITY> {
+    /// The time the LED holds `start_level` (off by default) before starting its on/off cycle.
     pub initial_delay: Duration,
     /// A vector of cyclic durations that alternate the LED's state.
-    pub on_off_durations: Vec<Duration, SCHEDULE_CAPACITY>,
+    pub on_off_durations: Vec<Duration, N>,
+    /// How many times to run the cycle, or `None` to run it forever.
+    pub repeat: Option<NonZeroU32>,
+    /// The level the LED holds during the `initial_delay`; the cycle starts at the other level.
+    pub start_level: Level,
 }
 
-impl Schedule {
+impl<const N: usize> Default for Schedule<N> {
+    fn default() -> Self {
+        Self {
+            initial_delay: ZERO_DELAY,
+            on_off_durations: Vec::new(),
+            repeat: None,
+            start_level: Level::Low,
+        }
+    }
+}
+
+impl<const N: usize> Schedule<N> {
+    /// Creates a `ScheduleBuilder` for assembling a schedule one segment at a time.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let schedule = Schedule::builder()
+    ///     .initial_delay(Duration::from_millis(100))
+    ///     .on(Duration::from_millis(200))?
+    ///     .off(Duration::from_millis(800))?
+    ///     .build()?;
+    /// ```
+    #[must_use]
+    pub fn builder() -> ScheduleBuilder<N> {
+        ScheduleBuilder::default()
+    }
+
     /// Creates a new `Schedule` instance.
     ///
     /// # Arguments
     ///
-    /// - `initial_delay`: The time the LED remains off before starting its on/off cycle.
+    /// - `initial_delay`: The time the LED holds `start_level` (off) before starting its cycle.
     /// - `on_off_durations`: A vector of cyclic durations that alternate the LED's state. It must have an even number of elements.
     ///
     /// # Errors
     ///
-    /// Returns an error if the `on_off_durations` length is not even.
-    fn new(
-        initial_delay: Duration,
-        on_off_durations: Vec<Duration, SCHEDULE_CAPACITY>,
-    ) -> Result<Self> {
-        if on_off_durations.len() & 1 != 0 {
+    /// Returns an error if the `on_off_durations` length is not even, or if any duration but the
+    /// last is zero.
+    fn new(initial_delay: Duration, on_off_durations: Vec<Duration, N>) -> Result<Self> {
+        let schedule = Self {
+            initial_delay,
+            on_off_durations,
+            repeat: None,
+            start_level: Level::Low,
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Checks the rules every `Schedule` must follow (see `Schedule`), for schedules assembled
+    /// field by field rather than through a constructor. Every constructor runs the same checks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ScheduleCycleLengthMustBeEven` if `on_off_durations` has an odd length,
+    /// or `Error::ScheduleZeroDuration` if any duration but the last is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.on_off_durations.len() & 1 != 0 {
             // detect odd length
             return Err(Error::ScheduleCycleLengthMustBeEven);
         }
-
-        Ok(Self {
-            initial_delay,
-            on_off_durations,
-        })
+        if self.on_off_durations.split_last().is_some_and(|(_, init)| init.contains(&ZERO_DELAY)) {
+            return Err(Error::ScheduleZeroDuration);
+        }
+        Ok(())
     }
 
     /// Creates a new `Schedule` from an initial delay and a slice of durations.
     ///
     /// # Arguments
     ///
-    /// - `initial_delay`: The time the LED remains off before starting its on/off cycle.
+    /// - `initial_delay`: The time the LED holds `start_level` (off) before starting its cycle.
     /// - `slice`: A slice of cyclic durations that alternate the LED's state. It must have an even number of elements.
     ///
     /// # Errors
     ///
-    /// Returns an error if the slice length is not even or if the slice exceeds the capacity of the vector.
+    /// Returns an error if the slice length is not even, if a duration but the last is zero or
+    /// if the slice exceeds the capacity of the vector.
     /// ```
     fn from_slice(initial_delay: Duration, slice: &[Duration]) -> Result<Self> {
         let on_off_durations =
@@ -65,66 +140,461 @@ impl Schedule {
         Self::new(initial_delay, on_off_durations)
     }
 
+    /// Creates a schedule from a fixed number of durations, for patterns built into the firmware.
+    ///
+    /// The length rules are checked at compile time instead of returning an error: `M` must be
+    /// even and no more than `N`, or the build fails. (`heapless` can't fill a `Vec` in a `const`
+    /// context, so the schedule itself is still built at run time.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if a duration but the last is zero, which can't be checked at compile time. For
+    /// the constants this is meant for, that shows up the first time the pattern is used.
+    #[must_use]
+    pub fn new_const<const M: usize>(initial_delay: Duration, durations: [Duration; M]) -> Self {
+        const {
+            assert!(M & 1 == 0, "A schedule must have an even number of durations");
+            assert!(M <= N, "The durations exceed the schedule's capacity");
+        }
+        let built = Vec::from_slice(&durations)
+            .map_err(|()| Error::ScheduleCapacityExceeded)
+            .and_then(|on_off_durations| Self::new(initial_delay, on_off_durations));
+        // `defmt::panic!` would expand to `unsafe` code, which a `Deserialize` derive frowns on.
+        match built {
+            Ok(schedule) => schedule,
+            Err(err) => panic!("Invalid built-in schedule: {err}"),
+        }
+    }
+
+    /// Like `from_slice`, but instead of failing when `slice` holds more than `N` durations, keeps
+    /// as many as fit and says whether any were dropped.
+    ///
+    /// The kept durations are the first `N` (or all of `slice`, if shorter), less the last one if
+    /// that would leave an odd number, so that every "on" segment keeps its "off" segment. An
+    /// odd-length `slice` therefore always loses its last duration, even if it fits. Prefer
+    /// `from_slice` unless showing the beginning of a pattern beats showing nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a kept duration but the last is zero.
+    pub fn from_slice_truncating(
+        initial_delay: Duration,
+        slice: &[Duration],
+    ) -> Result<(Self, bool)> {
+        let len = slice.len().min(N) & !1;
+        let kept = slice.get(..len).unwrap_or_default();
+        Ok((Self::from_slice(initial_delay, kept)?, len < slice.len()))
+    }
+
     /// Creates a schedule with a fast flashing `on_off_durations` with no initial delay.
-    #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn fast_no_delay() -> Result<Self> {
-        Self::from_slice(ZERO_DELAY, &[FAST_FLASH_DELAY, FAST_FLASH_DELAY])
+    #[must_use]
+    pub fn fast_no_delay() -> Self {
+        Self::new_const(ZERO_DELAY, [FAST_FLASH_DELAY, FAST_FLASH_DELAY])
     }
 
     /// Creates a schedule with a fast flashing `on_off_durations` after a short initial delay.
-    #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn fast_with_delay() -> Result<Self> {
-        Self::from_slice(FAST_FLASH_DELAY, &[FAST_FLASH_DELAY, FAST_FLASH_DELAY])
+    #[must_use]
+    pub fn fast_with_delay() -> Self {
+        Self::new_const(FAST_FLASH_DELAY, [FAST_FLASH_DELAY, FAST_FLASH_DELAY])
     }
 
     /// Creates a schedule with a slow flashing `on_off_durations` with no initial delay.
-    #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn slow_no_delay() -> Result<Self> {
-        Self::from_slice(ZERO_DELAY, &[SLOW_FLASH_DELAY, SLOW_FLASH_DELAY])
+    #[must_use]
+    pub fn slow_no_delay() -> Self {
+        Self::new_const(ZERO_DELAY, [SLOW_FLASH_DELAY, SLOW_FLASH_DELAY])
     }
 
     /// Creates a schedule with a slow flashing `on_off_durations` after a short initial delay.
+    #[must_use]
+    pub fn slow_even() -> Self {
+        Self::new_const(SLOW_FLASH_DELAY, [SLOW_FLASH_DELAY, SLOW_FLASH_DELAY])
+    }
+
+    /// Like `fast_no_delay`, but flashing at `config.fast_flash_delay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ScheduleZeroDuration` if the delay is zero.
+    pub fn fast_no_delay_with(config: &AppConfig) -> Result<Self> {
+        Self::flashing(ZERO_DELAY, config.fast_flash_delay)
+    }
+
+    /// Like `fast_with_delay`, but flashing (and first waiting) at `config.fast_flash_delay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ScheduleZeroDuration` if the delay is zero.
+    pub fn fast_with_delay_with(config: &AppConfig) -> Result<Self> {
+        Self::flashing(config.fast_flash_delay, config.fast_flash_delay)
+    }
+
+    /// Like `slow_no_delay`, but flashing at `config.slow_flash_delay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ScheduleZeroDuration` if the delay is zero.
+    pub fn slow_no_delay_with(config: &AppConfig) -> Result<Self> {
+        Self::flashing(ZERO_DELAY, config.slow_flash_delay)
+    }
+
+    /// Creates a schedule that is on for `delay`, then off for `delay`, after `initial_delay`.
+    fn flashing(initial_delay: Duration, delay: Duration) -> Result<Self> {
+        let mut on_off_durations = Vec::new();
+        on_off_durations
+            .extend_from_slice(&[delay, delay])
+            .map_err(|()| Error::ScheduleCapacityExceeded)?;
+        Self::new(initial_delay, on_off_durations)
+    }
+
+    /// Creates a schedule that blinks once per `period`, on for `duty_percent` of it.
+    ///
+    /// 100 percent is steadily on (like `on()`) and 0 percent steadily off (like `off()`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidDutyCycle` if `duty_percent` is more than 100, an error if `period`
+    /// is so short that the "on" time rounds to zero ticks, or an error if the arithmetic
+    /// overflows.
+    pub fn duty_cycle(period: Duration, duty_percent: u8) -> Result<Self> {
+        if duty_percent > 100 {
+            return Err(Error::InvalidDutyCycle);
+        }
+        if duty_percent == 0 {
+            // A zero-length "on" segment isn't allowed, and wouldn't show anyway.
+            return Ok(Self::off());
+        }
+        let on = period
+            .as_ticks()
+            .checked_mul(u64::from(duty_percent))
+            .and_then(|ticks| ticks.checked_div(100))
+            .map(Duration::from_ticks)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let off = period.checked_sub(on).ok_or(Error::ArithmeticOverflow)?;
+        Self::from_slice(ZERO_DELAY, &[on, off])
+    }
+
+    /// Creates a schedule that flashes the LED `count` times and then leaves it off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `on` is zero or if the schedule's capacity is exceeded.
+    pub fn pulses(count: NonZeroU32, on: Duration, off: Duration) -> Result<Self> {
+        let mut pulses = Self::from_slice(ZERO_DELAY, &[on, off])?;
+        pulses.repeat = Some(count);
+        Ok(pulses)
+    }
+
+    /// Creates a schedule whose cycle is `times` flashes of `on` followed by `off`, e.g. a burst
+    /// of three blinks that repeats forever.
+    ///
+    /// To flash a fixed number of times and then stop, set `repeat` to `Some(1)` or use
+    /// `pulses`. With `times` zero, the schedule is empty, i.e. the LED stays off.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ScheduleCapacityExceeded` if `2 * times` is more than the schedule's
+    /// capacity, and `Error::ScheduleZeroDuration` if `on` is zero (or `off` is, with `times`
+    /// above one).
+    pub fn repeat_pattern(on: Duration, off: Duration, times: usize) -> Result<Self> {
+        if times.checked_mul(2).is_none_or(|len| len > N) {
+            return Err(Error::ScheduleCapacityExceeded);
+        }
+        let mut on_off_durations = Vec::new();
+        for _ in 0..times {
+            on_off_durations
+                .extend_from_slice(&[on, off])
+                .map_err(|()| Error::ScheduleCapacityExceeded)?;
+        }
+        Self::new(ZERO_DELAY, on_off_durations)
+    }
+
+    /// Creates a "breathing" schedule whose flashes lengthen linearly from `min` to `max` over
+    /// `steps` on/off pairs, then start over.
+    ///
+    /// Each pair is on and off for the same duration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min` is greater than `max`, if `min` is zero (the first pair would
+    /// be zero-length), if `steps` pairs exceed the schedule's capacity, or if the interpolation
+    /// overflows.
+    pub fn ramp(steps: u8, min: Duration, max: Duration) -> Result<Self> {
+        let span = max.checked_sub(min).ok_or(Error::ScheduleRampMinExceedsMax)?.as_ticks();
+        let last_step = u64::from(steps.saturating_sub(1)).max(1);
+
+        let mut ramp = Vec::default();
+        for step in 0..steps {
+            let duration = span
+                .checked_mul(u64::from(step))
+                .and_then(|ticks| ticks.checked_div(last_step))
+                .and_then(|ticks| ticks.checked_add(min.as_ticks()))
+                .ok_or(Error::ArithmeticOverflow)
+                .map(Duration::from_ticks)?;
+            ramp.push(duration).map_err(|_| Error::ScheduleCapacityExceeded)?;
+            ramp.push(duration).map_err(|_| Error::ScheduleCapacityExceeded)?;
+        }
+        Self::new(ZERO_DELAY, ramp)
+    }
+
+    /// Creates a schedule for breathing from fast to slow flashes.
     #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn slow_even() -> Result<Self> {
-        Self::from_slice(SLOW_FLASH_DELAY, &[SLOW_FLASH_DELAY, SLOW_FLASH_DELAY])
+    pub fn breathing() -> Result<Self> {
+        Self::ramp(BREATHING_STEPS, FAST_BREATH_DELAY, SLOW_BREATH_DELAY)
     }
 
-    /// Creates a schedule with the LED always on.
+    /// Creates a schedule with a short blip once per `HEARTBEAT_PERIOD`, showing that the firmware
+    /// is alive.
     #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn on() -> Result<Self> {
-        Self::from_slice(ZERO_DELAY, &[ONE_DAY, ZERO_DELAY])
+    pub fn heartbeat() -> Result<Self> {
+        let off = HEARTBEAT_PERIOD.checked_sub(HEARTBEAT_BLIP).ok_or(Error::ArithmeticOverflow)?;
+        Self::from_slice(ZERO_DELAY, &[HEARTBEAT_BLIP, off])
+    }
+
+    /// Creates a schedule with the LED always on.
+    #[must_use]
+    pub fn on() -> Self {
+        Self::new_const(ZERO_DELAY, [ONE_DAY, ZERO_DELAY])
     }
 
     /// Creates a schedule with the LED always off.
-    #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
-    pub fn off() -> Result<Self> {
-        Ok(Self::default())
+    #[must_use]
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the LED stays at one level from the start, i.e. never toggles.
+    ///
+    /// Exactly two shapes are recognized, matching `off()` and `on()`:
+    ///
+    /// * No `on_off_durations` at all: the LED is off throughout (whatever the `initial_delay`
+    ///   and `repeat`).
+    /// * A single "on" followed by a zero "off", with no `initial_delay` and no `repeat`: the LED
+    ///   is on throughout. (The zero "off" lasts no time, so the LED never visibly goes off.)
+    ///
+    /// Anything else is reported as toggling, even if it toggles only once, such as `on()` after
+    /// an initial delay, or with a `repeat` that eventually turns it off. With a `start_level`
+    /// of `Level::High`, the same two shapes are static with the levels swapped.
+    #[must_use]
+    pub fn is_static(&self) -> bool {
+        match self.on_off_durations.as_slice() {
+            [] => true,
+            [_, off] => {
+                *off == ZERO_DELAY && self.initial_delay == ZERO_DELAY && self.repeat.is_none()
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns one pass through `on_off_durations` as the `Level` the LED should hold and for
+    /// how long.
+    ///
+    /// Steps start (right after the `initial_delay`) with the opposite of `start_level`, i.e.
+    /// with `Level::High` by default, and alternate from there.
+    pub fn steps(&self) -> impl Iterator<Item = (Level, Duration)> + '_ {
+        let first = Level::from(self.start_level == Level::Low);
+        let second = self.start_level;
+        [first, second].into_iter().cycle().zip(self.on_off_durations.iter().copied())
+    }
+
+    /// Returns how many durations `on_off_durations` holds.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.on_off_durations.len()
+    }
+
+    /// Returns whether `on_off_durations` is empty, as it is for `Schedule::off()`.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.on_off_durations.is_empty()
+    }
+
+    /// Returns how many more durations fit in `on_off_durations` before it reaches its capacity
+    /// of `N` (`SCHEDULE_CAPACITY` by default), e.g. to decide whether another Morse letter fits
+    /// before appending it. A schedule's length stays even, so only pairs are worth appending.
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.on_off_durations.capacity().saturating_sub(self.on_off_durations.len())
+    }
+
+    /// Returns how long one full pass through `on_off_durations` takes.
+    ///
+    /// An empty schedule has a cycle duration of `ZERO_DELAY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sum overflows.
+    pub fn cycle_duration(&self) -> Result<Duration> {
+        self.on_off_durations
+            .iter()
+            .try_fold(ZERO_DELAY, |total, duration| duration_add(total, *duration))
+    }
+
+    /// Returns the `initial_delay` plus one full pass through `on_off_durations`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sum overflows.
+    pub fn total_duration(&self) -> Result<Duration> {
+        duration_add(self.initial_delay, self.cycle_duration()?)
+    }
+
+    /// Creates a copy of this schedule that runs half a cycle later (see `phase_shifted`), so
+    /// that it takes turns with the original, as in `LedPair::schedule_alternate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cycle's duration or the new `initial_delay` overflows.
+    pub fn half_cycle_later(&self) -> Result<Self> {
+        let half_cycle = self.cycle_duration()?.checked_div(2).ok_or(Error::ArithmeticOverflow)?;
+        self.phase_shifted(half_cycle)
+    }
+
+    /// Creates a copy of this schedule that runs the same cycle `by` later, by lengthening the
+    /// `initial_delay`.
+    ///
+    /// Only the phase matters, so `by` is taken modulo `cycle_duration()`; shifting by a whole
+    /// number of cycles changes nothing. An empty cycle is left as is. For example, this runs
+    /// `led1` half a cycle behind `led0`:
+    ///
+    /// ```rust,ignore
+    /// let half_cycle = base.cycle_duration()?.checked_div(2).ok_or(Error::ArithmeticOverflow)?;
+    /// led1.schedule(base.phase_shifted(half_cycle)?);
+    /// led0.schedule(base);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cycle's duration or the new `initial_delay` overflows.
+    pub fn phase_shifted(&self, by: Duration) -> Result<Self> {
+        let cycle = self.cycle_duration()?.as_ticks();
+        let shift = Duration::from_ticks(by.as_ticks().checked_rem(cycle).unwrap_or(0));
+        let mut shifted = self.clone();
+        shifted.initial_delay = duration_add(self.initial_delay, shift)?;
+        Ok(shifted)
+    }
+
+    /// Creates a copy of this schedule with each of the `on_off_durations` moved by a random
+    /// amount of up to `max_jitter` either way, e.g. for a candle-like flicker.
+    ///
+    /// A duration never shrinks below one tick, and a trailing `ZERO_DELAY` (as in `on()`) stays
+    /// zero, so the result follows the same rules as the original. The `initial_delay` is kept.
+    /// The jitter is drawn once, so the jittered cycle repeats exactly; to keep the effect
+    /// lively, send a freshly jittered schedule every so often. See `Rng` for how random (or
+    /// not) the amounts are.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut rng = Rng::new();
+    /// let candle = Schedule::duty_cycle(Duration::from_millis(120), 80)?;
+    /// loop {
+    ///     led0.schedule(candle.jittered(Duration::from_millis(40), &mut rng)?);
+    ///     Timer::after(Duration::from_secs(2)).await;
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_jitter` is so large that the arithmetic overflows.
+    pub fn jittered(&self, max_jitter: Duration, rng: &mut Rng) -> Result<Self> {
+        let max = max_jitter.as_ticks();
+        let span = max.checked_mul(2).ok_or(Error::ArithmeticOverflow)?;
+        let mut jittered = self.clone();
+        for duration in &mut jittered.on_off_durations {
+            if *duration == ZERO_DELAY {
+                continue;
+            }
+            // Move by `offset - max`, i.e. somewhere from `-max` to `+max`.
+            let offset = rng.up_to(span);
+            let ticks = duration.as_ticks().checked_add(offset).ok_or(Error::ArithmeticOverflow)?;
+            *duration = Duration::from_ticks(ticks.saturating_sub(max).max(1));
+        }
+        Ok(jittered)
+    }
+
+    /// Creates the photographic negative of this schedule: once the cycle starts, the LED is off
+    /// whenever this schedule's is on, and on whenever it is off.
+    ///
+    /// The first "on" segment is moved to the end of the cycle and added to the `initial_delay`,
+    /// so the cycle starts with what was the first "off" segment. During the original
+    /// `initial_delay` both hold `start_level`, which is kept, as is `repeat`. (To light the LED
+    /// during the `initial_delay` too, set `start_level` instead.) For example,
+    /// `initial_delay=0 on_off=[100, 300]` mirrors to `initial_delay=100 on_off=[300, 100]`. An
+    /// empty schedule mirrors to itself.
+    ///
+    /// Unlike `phase_shifted`, which delays the same pattern, this inverts it. The two agree for
+    /// a single on/off pair of equal lengths, such as the fast and slow flashes, but a blip like
+    /// the heartbeat mirrors to a brief gap instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new `initial_delay` overflows, or if a trailing `ZERO_DELAY` (as in
+    /// `Schedule::on()`) would end up inside the cycle; mirror `on()` with `off()` instead.
+    pub fn mirror(&self) -> Result<Self> {
+        let Some((&first_on, rest)) = self.on_off_durations.split_first() else {
+            return Ok(self.clone());
+        };
+        let mut on_off_durations: Vec<Duration, N> =
+            Vec::from_slice(rest).map_err(|()| Error::ScheduleCapacityExceeded)?;
+        on_off_durations.push(first_on).map_err(|_| Error::ScheduleCapacityExceeded)?;
+        let initial_delay = duration_add(self.initial_delay, first_on)?;
+        let mut mirrored = Self::new(initial_delay, on_off_durations)?;
+        mirrored.repeat = self.repeat;
+        mirrored.start_level = self.start_level;
+        Ok(mirrored)
     }
 
-    /// Creates a schedule for the "SOS" Morse code `on_off_durations`.
+    /// Creates a copy of this schedule with every duration (including the `initial_delay`)
+    /// multiplied by `numerator / denominator`.
+    ///
+    /// For example, `scaled(1, 2)` runs the pattern at twice the speed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `denominator` is zero, if scaling a duration overflows, or if a
+    /// duration but the last rounds down to zero.
+    pub fn scaled(&self, numerator: u32, denominator: u32) -> Result<Self> {
+        if denominator == 0 {
+            return Err(Error::ScheduleScaleDivideByZero);
+        }
+        let scale = |duration: Duration| {
+            duration
+                .as_ticks()
+                .checked_mul(u64::from(numerator))
+                .and_then(|ticks| ticks.checked_div(u64::from(denominator)))
+                .ok_or(Error::ArithmeticOverflow)
+                .map(Duration::from_ticks)
+        };
+
+        let mut on_off_durations = self.on_off_durations.clone();
+        for duration in &mut on_off_durations {
+            *duration = scale(*duration)?;
+        }
+
+        let scaled = Self {
+            initial_delay: scale(self.initial_delay)?,
+            on_off_durations,
+            repeat: self.repeat,
+            start_level: self.start_level,
+        };
+        scaled.validate()?;
+        Ok(scaled)
+    }
+
+    /// Creates a schedule for the "SOS" Morse code `on_off_durations`: `SOS_UNITS`, then
+    /// `dot_after` dots off before the signal repeats.
     fn sos(dot_delay: u64, dot_after: u64, millis_per_dot: u64) -> Result<Self> {
-        let mut sos = Vec::default();
-        sos.extend_from_slice(&MORSE_S_MILLIS).map_err(|()| Error::ScheduleCapacityExceeded)?;
-        sos.push(MORSE_DASH_MILLIS).map_err(|_| Error::ScheduleCapacityExceeded)?;
-        sos.extend_from_slice(&MORSE_O_MILLIS).map_err(|()| Error::ScheduleCapacityExceeded)?;
-        sos.push(MORSE_DASH_MILLIS).map_err(|_| Error::ScheduleCapacityExceeded)?;
-        sos.extend_from_slice(&MORSE_S_MILLIS).map_err(|()| Error::ScheduleCapacityExceeded)?;
+        let mut sos: Vec<Duration, N> =
+            Vec::from_slice(&SOS_UNITS).map_err(|()| Error::ScheduleCapacityExceeded)?;
         sos.push(Duration::from_millis(dot_after)).map_err(|_| Error::ScheduleCapacityExceeded)?;
 
         // Adjust each duration by multiplying with millis_per_dot, checking for overflow
         for duration in &mut sos {
-            *duration = duration
-                .as_ticks()
-                .checked_mul(millis_per_dot)
-                .ok_or(Error::ArithmeticOverflow)
-                .map(Duration::from_ticks)?;
+            *duration = duration_mul(*duration, millis_per_dot)?;
         }
 
         // Calculate the initial delay, checking for overflow
-        let initial_delay = dot_delay
-            .checked_mul(millis_per_dot)
-            .ok_or(Error::ArithmeticOverflow)
-            .map(Duration::from_ticks)?;
+        let initial_delay = duration_mul(Duration::from_ticks(dot_delay), millis_per_dot)?;
 
         Self::new(initial_delay, sos)
     }
@@ -132,7 +602,7 @@ impl Schedule {
     /// Creates a schedule for the "SOS" with each dot at 120 milliseconds.
     #[expect(clippy::missing_errors_doc, reason = "These inputs avoid errors.")]
     pub fn sos_slow() -> Result<Self> {
-        Self::sos(5, 50, 120)
+        Self::sos(5, SOS_SLOW_DOTS_AFTER, SOS_SLOW_MILLIS_PER_DOT)
     }
 
     /// Creates a schedule for the "SOS" with each dot at 60 milliseconds and a long initial delay.
@@ -140,4 +610,492 @@ impl Schedule {
     pub fn sos_fast() -> Result<Self> {
         Self::sos(100, 10, 60)
     }
+
+    /// Creates a schedule that flashes `text` in Morse code, with each dot lasting
+    /// `millis_per_dot` milliseconds.
+    ///
+    /// Timing follows the ITU standard: a dot is 1 unit, a dash 3 units, the gap between the
+    /// symbols of a letter 1 unit, the gap between letters 3 units and the gap between words
+    /// 7 units. The pattern ends with a word gap so that it repeats cleanly. Letters are
+    /// case-insensitive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` contains a character not in the Morse table, if the encoded
+    /// pattern exceeds the schedule's capacity, if `millis_per_dot` is zero, or if scaling by
+    /// `millis_per_dot` overflows.
+    ///
+    /// To show the beginning of a message too long for `N`, encode it into a larger
+    /// `Schedule` and pass its `on_off_durations` to `from_slice_truncating`.
+    pub fn from_morse(text: &str, millis_per_dot: u64) -> Result<Self> {
+        let mut morse: Vec<Duration, N> = Vec::default();
+        for character in text.chars() {
+            if character == ' ' {
+                // A space widens the preceding gap to a word gap.
+                if let Some(gap) = morse.last_mut() {
+                    *gap = (*gap).max(MORSE_WORD_GAP_MILLIS);
+                }
+                continue;
+            }
+
+            let code = morse_for(character).ok_or(Error::UnsupportedMorseChar(character))?;
+            for symbol in code {
+                let on = match symbol {
+                    MorseSymbol::Dot => MORSE_DOT_MILLIS,
+                    MorseSymbol::Dash => MORSE_DASH_MILLIS,
+                };
+                morse.push(on).map_err(|_| Error::ScheduleCapacityExceeded)?;
+                morse.push(MORSE_DOT_MILLIS).map_err(|_| Error::ScheduleCapacityExceeded)?;
+            }
+            // The last symbol of the letter is followed by a letter gap.
+            if let Some(gap) = morse.last_mut() {
+                *gap = (*gap).max(MORSE_LETTER_GAP_MILLIS);
+            }
+        }
+        // Separate the end of the message from its next repetition.
+        if let Some(gap) = morse.last_mut() {
+            *gap = MORSE_WORD_GAP_MILLIS;
+        }
+
+        // Adjust each duration by multiplying with millis_per_dot, checking for overflow
+        for duration in &mut morse {
+            *duration = duration_mul(*duration, millis_per_dot)?;
+        }
+
+        Self::new(ZERO_DELAY, morse)
+    }
+
+    /// Decodes a schedule made by `from_morse` with the same `millis_per_dot` back into text.
+    ///
+    /// Each "on" duration is read as a dot or a dash, and each "off" duration as the gap within
+    /// a letter, between letters or between words, allowing `MORSE_DECODE_SLACK_PERCENT` either
+    /// way of the nominal length. The `initial_delay` and `start_level` are ignored (so the
+    /// segments are read as on, off, on, ...), and so is the final word gap,
+    /// which only separates repetitions. Letters come back in upper case and runs of spaces as
+    /// one space.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::MorseDecodeAmbiguous` if a duration doesn't fall within the slack of any
+    /// symbol or gap, or if a letter's symbols aren't in the Morse table.
+    pub fn to_morse(&self, millis_per_dot: u64) -> Result<String<N>> {
+        let dot = Duration::from_millis(millis_per_dot);
+        let mut text = String::new();
+        let mut symbols: Vec<MorseSymbol, MORSE_SYMBOL_CAPACITY> = Vec::new();
+        let mut durations = self.on_off_durations.iter().copied().peekable();
+        while let (Some(on), Some(off)) = (durations.next(), durations.next()) {
+            let symbol = match morse_units(on, dot, &[MORSE_DOT_MILLIS, MORSE_DASH_MILLIS])? {
+                0 => MorseSymbol::Dot,
+                _ => MorseSymbol::Dash,
+            };
+            symbols.push(symbol).map_err(|_| Error::MorseDecodeAmbiguous)?;
+            let gap = morse_units(
+                off,
+                dot,
+                &[MORSE_DOT_MILLIS, MORSE_LETTER_GAP_MILLIS, MORSE_WORD_GAP_MILLIS],
+            )?;
+            // Index 0 is the gap within a letter, 1 the gap after one and 2 the gap after a word.
+            if gap == 0 {
+                continue;
+            }
+            let (letter, _) = MORSE_ALPHABET
+                .iter()
+                .find(|&&(_, code)| code == symbols.as_slice())
+                .ok_or(Error::MorseDecodeAmbiguous)?;
+            text.push(*letter).map_err(|()| Error::MorseMessageTooLong)?;
+            symbols.clear();
+            if gap == 2 && durations.peek().is_some() {
+                text.push(' ').map_err(|()| Error::MorseMessageTooLong)?;
+            }
+        }
+        // A letter must end with a letter or word gap.
+        if symbols.is_empty() {
+            Ok(text)
+        } else {
+            Err(Error::MorseDecodeAmbiguous)
+        }
+    }
+}
+
+/// Builds the `Schedule` of a Morse message whose text is known at compile time, like
+/// `Schedule::from_morse` but with the durations worked out during the build.
+///
+/// `morse!("SOS")` uses `MORSE_MESSAGE_MILLIS_PER_DOT`; `morse!("SOS", 100)` sets the length of a
+/// dot in milliseconds. The text must be a `const` `&str`. A character other than a letter,
+/// digit or space, or a message with more durations than the `Schedule`'s capacity, fails the
+/// build. The durations themselves then cost nothing at run time; only copying them into the
+/// `Schedule` remains (see `Schedule::new_const`). For the bare array, use
+/// `shared_const::morse_durations` in a `const`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static SOS: [Duration; 18] = morse_durations::<{ morse_len("SOS") }>("SOS", 120);
+/// led0.schedule(morse!("SOS", 120));
+/// ```
+#[macro_export]
+macro_rules! morse {
+    ($text:expr) => {
+        $crate::morse!($text, $crate::shared_const::MORSE_MESSAGE_MILLIS_PER_DOT)
+    };
+    ($text:expr, $millis_per_dot:expr) => {
+        $crate::Schedule::new_const(
+            $crate::shared_const::ZERO_DELAY,
+            const {
+                $crate::shared_const::morse_durations::<{ $crate::shared_const::morse_len($text) }>(
+                    $text,
+                    $millis_per_dot,
+                )
+            },
+        )
+    };
+}
+
+/// Returns `duration` times `factor`, or `Error::ArithmeticOverflow` if the product doesn't fit
+/// a `Duration`.
+const fn duration_mul(duration: Duration, factor: u64) -> Result<Duration> {
+    match duration.as_ticks().checked_mul(factor) {
+        Some(ticks) => Ok(Duration::from_ticks(ticks)),
+        None => Err(Error::ArithmeticOverflow),
+    }
+}
+
+/// Returns `augend` plus `addend`, or `Error::ArithmeticOverflow` if the sum doesn't fit a
+/// `Duration`.
+const fn duration_add(augend: Duration, addend: Duration) -> Result<Duration> {
+    match augend.as_ticks().checked_add(addend.as_ticks()) {
+        Some(ticks) => Ok(Duration::from_ticks(ticks)),
+        None => Err(Error::ArithmeticOverflow),
+    }
+}
+
+/// The "SOS" signal in dots, with ITU timing: each symbol of `MORSE_S_MILLIS` and
+/// `MORSE_O_MILLIS` is followed by a 1-dot gap, except the last of a letter, which is followed by
+/// a 3-dot `MORSE_LETTER_GAP_MILLIS`. The gap after the final "S" is left to `Schedule::sos`.
+const SOS_UNITS: [Duration; 17] = sos_units();
+
+/// The length of a dot in `Schedule::sos_slow`, in milliseconds.
+const SOS_SLOW_MILLIS_PER_DOT: u64 = 120;
+
+/// How many dots `Schedule::sos_slow` stays off after the final "S" before repeating. This is
+/// longer than a 7-dot word gap on purpose, to set each SOS apart.
+const SOS_SLOW_DOTS_AFTER: u64 = 50;
+
+/// Joins `MORSE_S_MILLIS`, `MORSE_O_MILLIS` and `MORSE_S_MILLIS` with letter gaps.
+#[expect(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "Used with const, so any errors will be caught at compile time."
+)]
+const fn sos_units() -> [Duration; 17] {
+    let mut units = [MORSE_LETTER_GAP_MILLIS; 17];
+    let mut index = 0;
+    while index < 5 {
+        units[index] = MORSE_S_MILLIS[index];
+        units[index + 6] = MORSE_O_MILLIS[index];
+        units[index + 12] = MORSE_S_MILLIS[index];
+        index += 1;
+    }
+    units
+}
+
+// Check that `sos_slow()`'s `on_off_durations` are exactly the ITU timing at 120 ms a
+// dot: dots of 1 unit and dashes of 3, 1 unit between symbols, 3 units between letters, and at
+// least a 7-unit word gap before the signal repeats. (There's no host test harness, so this is
+// checked at compile time.)
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    const EXPECTED_MILLIS: [u64; 17] = [
+        120, 120, 120, 120, 120, 360, // S, letter gap
+        360, 120, 360, 120, 360, 360, // O, letter gap
+        120, 120, 120, 120, 120, // S
+    ];
+    let mut index = 0;
+    while index < EXPECTED_MILLIS.len() {
+        let ticks = SOS_UNITS[index].as_ticks() * SOS_SLOW_MILLIS_PER_DOT;
+        assert!(ticks == Duration::from_millis(EXPECTED_MILLIS[index]).as_ticks());
+        index += 1;
+    }
+    assert!(SOS_SLOW_DOTS_AFTER >= MORSE_WORD_GAP_MILLIS.as_millis());
+};
+
+// Check that `duration_mul` and `duration_add` report overflow at `u64::MAX` ticks rather than
+// wrapping, and are exact just below it.
+const _: () = {
+    let max = Duration::from_ticks(u64::MAX);
+    assert!(matches!(duration_mul(max, 2), Err(Error::ArithmeticOverflow)));
+    assert!(matches!(duration_mul(max, 1), Ok(product) if product.as_ticks() == u64::MAX));
+    assert!(matches!(duration_mul(max, 0), Ok(product) if product.as_ticks() == 0));
+    let one = Duration::from_ticks(1);
+    assert!(matches!(duration_add(max, one), Err(Error::ArithmeticOverflow)));
+    let almost = Duration::from_ticks(u64::MAX - 1);
+    assert!(matches!(duration_add(almost, one), Ok(sum) if sum.as_ticks() == u64::MAX));
+};
+
+/// Returns the index of the entry of `nominal` that `duration` matches, where each entry is a
+/// length in dots (as the `MORSE_*_MILLIS` constants are) and `dot` is one dot's length. A
+/// match may be off by up to `MORSE_DECODE_SLACK_PERCENT`.
+fn morse_units(duration: Duration, dot: Duration, nominal: &[Duration]) -> Result<usize> {
+    nominal
+        .iter()
+        .position(|units| {
+            let expected = dot.as_ticks().saturating_mul(units.as_millis());
+            let slack =
+                expected.saturating_mul(MORSE_DECODE_SLACK_PERCENT).checked_div(100).unwrap_or(0);
+            duration.as_ticks().abs_diff(expected) <= slack
+        })
+        .ok_or(Error::MorseDecodeAmbiguous)
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> Schedule<N> {
+    /// Decodes a schedule that `to_postcard` (or a host using `serde` and `postcard`) encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` isn't a valid encoding, holds more than `N` durations, holds
+    /// an odd number of them or holds a zero duration before the last.
+    pub fn from_postcard(bytes: &[u8]) -> Result<Self> {
+        Ok(postcard::from_bytes(bytes)?)
+    }
+
+    /// Encodes the schedule into `buffer` with `postcard`, returning the used part of `buffer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buffer` is too small.
+    pub fn to_postcard<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a mut [u8]> {
+        Ok(postcard::to_slice(self, buffer)?)
+    }
+}
+
+/// The wire format of a `Schedule`, with each duration in microseconds so that it doesn't depend
+/// on the tick rate.
+#[cfg(feature = "serde")]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct WireSchedule<const N: usize> {
+    initial_delay_micros: u64,
+    on_off_durations_micros: Vec<u64, N>,
+    repeat: Option<NonZeroU32>,
+    start_high: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> From<Schedule<N>> for WireSchedule<N> {
+    fn from(schedule: Schedule<N>) -> Self {
+        Self {
+            initial_delay_micros: schedule.initial_delay.as_micros(),
+            on_off_durations_micros: schedule
+                .on_off_durations
+                .iter()
+                .map(Duration::as_micros)
+                .collect(),
+            repeat: schedule.repeat,
+            start_high: schedule.start_level == Level::High,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> TryFrom<WireSchedule<N>> for Schedule<N> {
+    type Error = Error;
+
+    fn try_from(wire: WireSchedule<N>) -> Result<Self> {
+        let on_off_durations =
+            wire.on_off_durations_micros.into_iter().map(Duration::from_micros).collect();
+        let mut schedule =
+            Self::new(Duration::from_micros(wire.initial_delay_micros), on_off_durations)?;
+        schedule.repeat = wire.repeat;
+        schedule.start_level = Level::from(wire.start_high);
+        Ok(schedule)
+    }
+}
+
+/// Formats the schedule compactly with every duration in milliseconds, e.g.
+/// `initial_delay=250ms on_off=[250, 250]ms repeat=forever`. A `start_level` of `Level::High`
+/// adds ` start=high`.
+impl<const N: usize> fmt::Display for Schedule<N> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "initial_delay={}ms on_off=[", self.initial_delay.as_millis())?;
+        for (index, duration) in self.on_off_durations.iter().enumerate() {
+            if index > 0 {
+                write!(formatter, ", ")?;
+            }
+            write!(formatter, "{}", duration.as_millis())?;
+        }
+        match self.repeat {
+            Some(repeat) => write!(formatter, "]ms repeat={repeat}")?,
+            None => write!(formatter, "]ms repeat=forever")?,
+        }
+        if self.start_level == Level::High {
+            write!(formatter, " start=high")?;
+        }
+        Ok(())
+    }
+}
+
+/// Logs the schedule in the same layout as its `Display` implementation.
+#[expect(
+    clippy::missing_trait_methods,
+    reason = "The remaining methods are internal to defmt and have correct defaults."
+)]
+impl<const N: usize> defmt::Format for Schedule<N> {
+    fn format(&self, formatter: defmt::Formatter<'_>) {
+        defmt::write!(formatter, "initial_delay={=u64}ms on_off=[", self.initial_delay.as_millis());
+        for (index, duration) in self.on_off_durations.iter().enumerate() {
+            if index > 0 {
+                defmt::write!(formatter, ", ");
+            }
+            defmt::write!(formatter, "{=u64}", duration.as_millis());
+        }
+        if let Some(repeat) = self.repeat {
+            defmt::write!(formatter, "]ms repeat={=u32}", repeat.get());
+        } else {
+            defmt::write!(formatter, "]ms repeat=forever");
+        }
+        if self.start_level == Level::High {
+            defmt::write!(formatter, " start=high");
+        }
+    }
+}
+
+/// Builds a `Schedule` incrementally.
+///
+/// Segments alternate starting with "on", so calls to `on` and `off` must alternate as well:
+/// a second `on` in a row (or `off`) is an error. (With `start_level(Level::High)`, they start
+/// with "off" instead, which `build` checks.) Create one with `Schedule::builder()`.
+#[derive(Debug, Default)]
+pub struct ScheduleBuilder<const N: usize = SCHEDULE_CAPACITY> {
+    initial_delay: Duration,
+    on_off_durations: Vec<Duration, N>,
+    repeat: Option<NonZeroU32>,
+    start_high: bool,
+    /// Whether the first segment is "on", once there is one.
+    first_on: Option<bool>,
+}
+
+impl<const N: usize> ScheduleBuilder<N> {
+    /// Sets the time the LED holds its start level (off unless set with `start_level()`) before
+    /// starting its on/off cycle.
+    #[must_use]
+    pub const fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Sets the level the LED holds during the initial delay (see `Schedule::start_level`).
+    #[must_use]
+    pub const fn start_level(mut self, level: Level) -> Self {
+        self.start_high = matches!(level, Level::High);
+        self
+    }
+
+    /// Sets how many times the cycle runs before the LED is held off.
+    #[must_use]
+    pub const fn repeat(mut self, count: NonZeroU32) -> Self {
+        self.repeat = Some(count);
+        self
+    }
+
+    /// Appends a segment during which the LED is on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the last segment was also "on", or if the schedule's capacity is
+    /// exceeded.
+    pub fn on(self, duration: Duration) -> Result<Self> {
+        self.push(true, duration)
+    }
+
+    /// Appends a segment during which the LED is off.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the last segment was also "off", or if the schedule's capacity is
+    /// exceeded.
+    pub fn off(self, duration: Duration) -> Result<Self> {
+        self.push(false, duration)
+    }
+
+    fn push(mut self, on: bool, duration: Duration) -> Result<Self> {
+        let even = self.on_off_durations.len() & 1 == 0;
+        if !segment_in_order(self.first_on, even, on) {
+            return Err(Error::ScheduleSegmentOutOfOrder);
+        }
+        self.on_off_durations.push(duration).map_err(|_| Error::ScheduleCapacityExceeded)?;
+        self.first_on.get_or_insert(on);
+        Ok(self)
+    }
+
+    /// Finishes building the `Schedule`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an "on" segment was not followed by an "off" segment, i.e. an odd
+    /// number of segments was pushed, if the first segment is at the start level (e.g. `on`
+    /// first with `start_level(Level::High)`), or if a segment but the last is zero-length.
+    pub fn build(self) -> Result<Schedule<N>> {
+        if !first_segment_in_order(self.first_on, self.start_high) {
+            return Err(Error::ScheduleSegmentOutOfOrder);
+        }
+        let mut schedule = Schedule::new(self.initial_delay, self.on_off_durations)?;
+        schedule.repeat = self.repeat;
+        schedule.start_level = Level::from(self.start_high);
+        Ok(schedule)
+    }
+}
+
+/// Returns whether `ScheduleBuilder` may push a segment at level `on` (`true` for "on") at an
+/// `even` position, after segments whose first was at `first_on`: segments at even positions
+/// share the first segment's level, and the others take the opposite one.
+const fn segment_in_order(first_on: Option<bool>, even: bool, on: bool) -> bool {
+    match first_on {
+        Some(first) => (even == first) == on,
+        None => true,
+    }
+}
+
+/// Returns whether `ScheduleBuilder::build` accepts a first segment at `first_on`: it must be at
+/// the opposite of the start level.
+const fn first_segment_in_order(first_on: Option<bool>, start_high: bool) -> bool {
+    match first_on {
+        Some(first) => first != start_high,
+        None => true,
+    }
 }
+
+/// Returns whether `ScheduleBuilder` accepts `segments` (`true` for "on"), pushed in order and
+/// then built, as far as the order of levels goes.
+const fn segments_in_order(start_high: bool, segments: &[bool]) -> bool {
+    let mut first_on = None;
+    let mut even = true;
+    let mut rest = segments;
+    while let [on, tail @ ..] = rest {
+        if !segment_in_order(first_on, even, *on) {
+            return false;
+        }
+        if first_on.is_none() {
+            first_on = Some(*on);
+        }
+        even = !even;
+        rest = tail;
+    }
+    first_segment_in_order(first_on, start_high)
+}
+
+// Check that `ScheduleBuilder` takes alternating segments, from either start level, and rejects
+// two of a kind in a row or a first segment at the start level. (There's no host test harness,
+// so this is checked at compile time, through the same checks `push` and `build` run.)
+const _: () = {
+    assert!(segments_in_order(false, &[true, false]));
+    assert!(segments_in_order(false, &[true, false, true, false]));
+    assert!(segments_in_order(true, &[false, true]));
+    assert!(segments_in_order(true, &[false, true, false, true]));
+    assert!(!segments_in_order(false, &[true, true]));
+    assert!(!segments_in_order(false, &[true, false, false]));
+    assert!(!segments_in_order(false, &[false, true]));
+    assert!(!segments_in_order(true, &[true, false]));
+};
@@ -5,20 +5,221 @@ use embassy_time::Duration;
 /// Debounce delay for button inputs.
 pub const BUTTON_DEBOUNCE_DELAY: Duration = Duration::from_millis(10);
 
+/// How long a button may be held at boot before `Button::check_stuck` reports it as stuck.
+pub const BUTTON_STUCK_WINDOW: Duration = Duration::from_secs(1);
+
+/// How soon after one button is pressed the other must follow for the two to count as a chord
+/// (see `press_or_chord`). It must be shorter than `LONG_PRESS_DURATION`.
+pub const CHORD_WINDOW: Duration = Duration::from_millis(100);
+
 /// Duration to recognize a long button press.
 pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
 
+/// Duration to recognize a very long button press.
+pub const VERY_LONG_PRESS_DURATION: Duration = Duration::from_secs(3);
+
+/// How often `Button::press_with_autorepeat` repeats while the button is held.
+pub const AUTOREPEAT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Time after a tap's release within which another tap counts toward a double or triple press.
+pub const MULTI_PRESS_WINDOW: Duration = Duration::from_millis(300);
+
 /// Delay between flashes for fast blinking.
 pub const FAST_FLASH_DELAY: Duration = Duration::from_millis(250);
 
 /// Delay between flashes for slow blinking.
 pub const SLOW_FLASH_DELAY: Duration = Duration::from_millis(750);
 
+/// How often the heartbeat LED blips.
+pub const HEARTBEAT_PERIOD: Duration = Duration::from_secs(1);
+
+/// How long each heartbeat blip lasts.
+pub const HEARTBEAT_BLIP: Duration = Duration::from_millis(50);
+
+/// Number of on/off pairs in the breathing ramp.
+pub const BREATHING_STEPS: u8 = 8;
+
+/// Shortest flash of the breathing ramp.
+pub const FAST_BREATH_DELAY: Duration = Duration::from_millis(50);
+
+/// Longest flash of the breathing ramp.
+pub const SLOW_BREATH_DELAY: Duration = Duration::from_millis(400);
+
+/// An `Led` time scale, in per mille, that leaves schedules unchanged.
+pub const UNSCALED_TIME_PERMILLE: u32 = 1000;
+
+/// The speeds of the `SpeedCycle` state, slowest first, as per mille scales of the slow flash: a
+/// short press moves to the next one, wrapping around.
+pub const SPEED_CYCLE_PERMILLE: [u32; 3] = [1000, 500, 250];
+
+/// Time scale, in per mille, with the potentiometer fully counter-clockwise (four times slower).
+pub const SLOWEST_TIME_SCALE_PERMILLE: u32 = 4000;
+
+/// Time scale, in per mille, with the potentiometer fully clockwise (four times faster).
+pub const FASTEST_TIME_SCALE_PERMILLE: u32 = 250;
+
+/// Granularity of the potentiometer's time scale, so that ADC noise doesn't restart schedules.
+pub const TIME_SCALE_STEP_PERMILLE: u32 = 50;
+
+/// Largest reading of the RP2040's 12-bit ADC.
+pub const POTENTIOMETER_FULL_SCALE: u32 = 4095;
+
+/// How long `LedState::SosArmed` waits for the confirming press before going back to the state
+/// it was armed from.
+pub const SOS_ARM_WINDOW: Duration = Duration::from_secs(5);
+
+/// Each "on" and "off" of the strobe that `LedState::SosArmed` shows on both LEDs.
+pub const SOS_ARMED_FLASH: Duration = Duration::from_millis(60);
+
+/// How long the main loop may go unfed before the `Watchdog` resets the device.
+pub const WATCHDOG_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The "going to sleep" blinks that `Power::prepare_sleep` shows on both LEDs: a long blink and
+/// then a short one, each followed by a pause.
+pub const SLEEP_BLINKS: [Duration; 4] = [
+    Duration::from_millis(200),
+    Duration::from_millis(150),
+    Duration::from_millis(80),
+    Duration::from_millis(170),
+];
+
+/// How long `Power::prepare_sleep` takes: the sum of `SLEEP_BLINKS`.
+pub const SLEEP_BLINKS_DURATION: Duration = Duration::from_millis(600);
+
+// Check that `SLEEP_BLINKS_DURATION` is what `SLEEP_BLINKS` add up to.
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    let mut total = 0;
+    let mut index = 0;
+    while index < SLEEP_BLINKS.len() {
+        total += SLEEP_BLINKS[index].as_ticks();
+        index += 1;
+    }
+    assert!(total == SLEEP_BLINKS_DURATION.as_ticks());
+};
+
+/// How long `Power::sleep_until_pressed` lets other tasks run before the chip goes dormant.
+pub const DORMANT_SETTLE_DELAY: Duration = Duration::from_millis(20);
+
+/// The GPIO of the LED that the `fault-blink` panic handler blinks: the Pico's on-board LED.
+pub const FAULT_LED_PIN: usize = 25;
+
+/// Length of one Morse unit (a dot) of the `fault-blink` panic handler's SOS, in milliseconds.
+pub const FAULT_BLINK_UNIT_MILLIS: u32 = 150;
+
+/// How many times the `fault-blink` panic handler blinks SOS before halting.
+pub const FAULT_BLINK_REPEATS: u32 = 5;
+
+/// CPU cycles per millisecond at `embassy_rp`'s default 125 MHz system clock.
+pub const SYSTEM_CLOCK_CYCLES_PER_MILLI: u32 = 125_000;
+
+/// How often the potentiometer is read.
+pub const POTENTIOMETER_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the light sensor is read.
+pub const LIGHT_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Light sensor reading (out of `POTENTIOMETER_FULL_SCALE`) below which the room counts as dark
+/// and the LEDs dim.
+pub const DARK_THRESHOLD: u16 = 800;
+
+/// How far above `DARK_THRESHOLD` the light must rise before the LEDs go back to full
+/// brightness, so that a reading hovering at the threshold doesn't flicker them.
+pub const LIGHT_HYSTERESIS: u16 = 200;
+
+/// LED brightness, in percent, when the room is dark.
+pub const NIGHT_BRIGHTNESS: u8 = 10;
+
+/// How long the LEDs take to dim when the room goes dark, or to brighten when it gets light.
+pub const NIGHT_FADE_DURATION: Duration = Duration::from_secs(2);
+
 /// Zero duration, representing no delay.
 pub const ZERO_DELAY: Duration = Duration::from_millis(0);
 
-/// Maximum number of elements in a schedule.
-pub const SCHEDULE_CAPACITY: usize = 20;
+/// LED brightness, in percent, when fully on.
+pub const FULL_BRIGHTNESS: u8 = 100;
+
+/// How long `LedState::AlwaysOn` takes to ramp the LEDs from off to full brightness on entry,
+/// rather than snapping on.
+pub const SOFT_START_DURATION: Duration = Duration::from_millis(300);
+
+/// How often a fade updates the LED's brightness.
+pub const FADE_STEP_INTERVAL: Duration = Duration::from_millis(10);
+
+/// PWM counter steps per duty-cycle percent.
+pub const PWM_COUNTS_PER_PERCENT: u16 = 100;
+
+/// PWM counter wrap value, so one period is `FULL_BRIGHTNESS * PWM_COUNTS_PER_PERCENT` counts
+/// (about 12.5 kHz at the default system clock, well above visible flicker).
+pub const PWM_TOP: u16 = FULL_BRIGHTNESS as u16 * PWM_COUNTS_PER_PERCENT - 1;
+
+/// Pitch of the buzzer's tone.
+pub const BUZZER_TONE_HZ: u32 = 2000;
+
+/// Clock divider of the buzzer's PWM slice, slowing the 125 MHz system clock to 1.95 MHz.
+pub const BUZZER_CLOCK_DIVIDER: u8 = 64;
+
+/// Buzzer PWM counter wrap value: 1.95 MHz / (976 + 1) counts is about `BUZZER_TONE_HZ`.
+pub const BUZZER_PWM_TOP: u16 = 976;
+
+/// USB vendor ID of the serial console. 0xc0de is not allocated, so it's only for development.
+pub const USB_VENDOR_ID: u16 = 0xc0de;
+
+/// USB product ID of the serial console.
+pub const USB_PRODUCT_ID: u16 = 0xcafe;
+
+/// Largest USB packet the serial console sends or receives, in bytes.
+pub const SERIAL_PACKET_SIZE: u16 = 64;
+
+/// Longest line, in bytes, that the serial console accepts.
+pub const SERIAL_LINE_CAPACITY: usize = 128;
+
+/// Number of serial console commands that can wait to be handled.
+pub const SERIAL_COMMAND_QUEUE_LEN: usize = 4;
+
+/// Size of the Raspberry Pi Pico's flash, in bytes.
+pub const FLASH_SIZE: usize = 2 * 1024 * 1024;
+
+/// Size of the flash sector reserved for persisted state (the RP2040's erase size).
+pub const STATE_FLASH_SECTOR_SIZE: u32 = 4096;
+
+/// Flash offset of the sector reserved for persisted state. It is the last sector, which
+/// `memory.x` excludes from the program's `FLASH` region.
+pub const STATE_FLASH_OFFSET: u32 = 2 * 1024 * 1024 - STATE_FLASH_SECTOR_SIZE;
+
+/// Maximum number of `Led`s that `Led::new` can start.
+///
+/// Each `Led` runs in its own instance of a pooled Embassy task, and the pool's memory is reserved
+/// statically, so this should match the number of `Led`s the application creates, including
+/// those in any `Leds` group. Creating one more fails with `SpawnError::Busy`.
+pub const LED_POOL_SIZE: usize = 3;
+
+/// How many schedules an `LedChannel` holds before `Led::enqueue` waits for room.
+pub const LED_QUEUE_CAPACITY: usize = 4;
+
+/// How long `Led::new_retry` waits between attempts to spawn an `Led`'s task.
+pub const LED_SPAWN_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Size, in bytes, of the stack of the executor that `Led::new_on_core1` starts on `CORE1`.
+pub const CORE1_STACK_SIZE: usize = 4096;
+
+/// How many state transitions the `event-log` feature keeps.
+pub const EVENT_LOG_CAPACITY: usize = 16;
+
+/// How many brightness changes a `MockLed` (with the `mock` feature) keeps.
+pub const MOCK_LED_LOG_CAPACITY: usize = 32;
+
+/// Maximum number of elements in a schedule, enough for a Morse message of a few letters.
+pub const SCHEDULE_CAPACITY: usize = 64;
+
+/// Maximum length, in bytes, of the message flashed in the `AllMorse` state.
+pub const MORSE_MESSAGE_CAPACITY: usize = 16;
+
+/// Length of one Morse dot in the `AllMorse` state, in milliseconds.
+pub const MORSE_MESSAGE_MILLIS_PER_DOT: u64 = 150;
 
 /// Duration representing one day.
 pub const ONE_DAY: Duration = Duration::from_secs(60 * 60 * 24);
@@ -29,6 +230,215 @@ pub const MORSE_DOT_MILLIS: Duration = Duration::from_millis(1);
 /// Duration of three milliseconds.
 pub const MORSE_DASH_MILLIS: Duration = Duration::from_millis(3);
 
+/// Duration of the gap between letters (three dots).
+pub const MORSE_LETTER_GAP_MILLIS: Duration = Duration::from_millis(3);
+
+/// Duration of the gap between words (seven dots).
+pub const MORSE_WORD_GAP_MILLIS: Duration = Duration::from_millis(7);
+
+/// How far, in percent, a duration may stray from its nominal length and still be decoded by
+/// `Schedule::to_morse`.
+pub const MORSE_DECODE_SLACK_PERCENT: u64 = 20;
+
+/// The most symbols in any letter or digit of `MORSE_ALPHABET`.
+pub const MORSE_SYMBOL_CAPACITY: usize = 5;
+
+/// A dot or a dash, the two symbols of Morse code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum MorseSymbol {
+    /// A short mark, one unit long.
+    Dot,
+    /// A long mark, three units long.
+    Dash,
+}
+
+/// Morse code for the letters A-Z and digits 0-9. Look characters up with `morse_for`.
+pub const MORSE_ALPHABET: [(char, &[MorseSymbol]); 36] = {
+    use MorseSymbol::{Dash, Dot};
+    [
+        ('A', &[Dot, Dash]),
+        ('B', &[Dash, Dot, Dot, Dot]),
+        ('C', &[Dash, Dot, Dash, Dot]),
+        ('D', &[Dash, Dot, Dot]),
+        ('E', &[Dot]),
+        ('F', &[Dot, Dot, Dash, Dot]),
+        ('G', &[Dash, Dash, Dot]),
+        ('H', &[Dot, Dot, Dot, Dot]),
+        ('I', &[Dot, Dot]),
+        ('J', &[Dot, Dash, Dash, Dash]),
+        ('K', &[Dash, Dot, Dash]),
+        ('L', &[Dot, Dash, Dot, Dot]),
+        ('M', &[Dash, Dash]),
+        ('N', &[Dash, Dot]),
+        ('O', &[Dash, Dash, Dash]),
+        ('P', &[Dot, Dash, Dash, Dot]),
+        ('Q', &[Dash, Dash, Dot, Dash]),
+        ('R', &[Dot, Dash, Dot]),
+        ('S', &[Dot, Dot, Dot]),
+        ('T', &[Dash]),
+        ('U', &[Dot, Dot, Dash]),
+        ('V', &[Dot, Dot, Dot, Dash]),
+        ('W', &[Dot, Dash, Dash]),
+        ('X', &[Dash, Dot, Dot, Dash]),
+        ('Y', &[Dash, Dot, Dash, Dash]),
+        ('Z', &[Dash, Dash, Dot, Dot]),
+        ('0', &[Dash, Dash, Dash, Dash, Dash]),
+        ('1', &[Dot, Dash, Dash, Dash, Dash]),
+        ('2', &[Dot, Dot, Dash, Dash, Dash]),
+        ('3', &[Dot, Dot, Dot, Dash, Dash]),
+        ('4', &[Dot, Dot, Dot, Dot, Dash]),
+        ('5', &[Dot, Dot, Dot, Dot, Dot]),
+        ('6', &[Dash, Dot, Dot, Dot, Dot]),
+        ('7', &[Dash, Dash, Dot, Dot, Dot]),
+        ('8', &[Dash, Dash, Dash, Dot, Dot]),
+        ('9', &[Dash, Dash, Dash, Dash, Dot]),
+    ]
+};
+
+/// Returns the Morse code for `character`, or `None` if it is not a letter or digit.
+///
+/// Letters are case-insensitive.
+#[must_use]
+pub fn morse_for(character: char) -> Option<&'static [MorseSymbol]> {
+    let upper = character.to_ascii_uppercase();
+    MORSE_ALPHABET.iter().find(|(letter, _)| *letter == upper).map(|&(_, code)| code)
+}
+
+/// Like `morse_for`, but usable in `const` contexts, for ASCII bytes.
+#[expect(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "The index stays below the table's length."
+)]
+const fn morse_for_byte(byte: u8) -> Option<&'static [MorseSymbol]> {
+    let upper = byte.to_ascii_uppercase();
+    let mut index = 0;
+    while index < MORSE_ALPHABET.len() {
+        let (letter, code) = MORSE_ALPHABET[index];
+        if letter as u32 == upper as u32 {
+            return Some(code);
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Returns how many durations `morse_durations` makes of `text`: two per dot or dash.
+///
+/// # Panics
+///
+/// Panics if `text` holds anything but letters, digits and spaces. Used in a `const` (as in
+/// `morse!`), that fails the build instead.
+#[expect(
+    clippy::arithmetic_side_effects,
+    reason = "Used with const, so any errors will be caught at compile time."
+)]
+#[must_use]
+pub const fn morse_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut len = 0;
+    let mut index = 0;
+    while let [byte, ..] = bytes.split_at(index).1 {
+        if *byte != b' ' {
+            let Some(code) = morse_for_byte(*byte) else {
+                panic!("The Morse text holds a character with no Morse code");
+            };
+            len += code.len() * 2;
+        }
+        index += 1;
+    }
+    len
+}
+
+/// The on/off durations of `text` in Morse code, with each dot `millis_per_dot` long, exactly as
+/// `Schedule::from_morse` makes them. `M` must be `morse_len(text)`.
+///
+/// This is the `const` counterpart of `from_morse` behind the `morse!` macro.
+///
+/// # Panics
+///
+/// Panics if `text` holds anything but letters, digits and spaces, if `M` is wrong, if
+/// `millis_per_dot` is zero or if a duration overflows. Used in a `const`, that fails the build
+/// instead.
+#[expect(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "Used with const, so any errors will be caught at compile time."
+)]
+#[must_use]
+pub const fn morse_durations<const M: usize>(text: &str, millis_per_dot: u64) -> [Duration; M] {
+    assert!(millis_per_dot > 0, "A Morse dot must last longer than zero");
+    assert!(M == morse_len(text), "M must be the `morse_len` of the text");
+    let bytes = text.as_bytes();
+    let mut units = [ZERO_DELAY; M];
+    let mut len = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        index += 1;
+        // A space widens the preceding gap to a word gap.
+        if byte == b' ' {
+            if len > 0 && units[len - 1].as_ticks() < MORSE_WORD_GAP_MILLIS.as_ticks() {
+                units[len - 1] = MORSE_WORD_GAP_MILLIS;
+            }
+            continue;
+        }
+        let Some(code) = morse_for_byte(byte) else {
+            panic!("The Morse text holds a character with no Morse code");
+        };
+        let mut symbol = 0;
+        while symbol < code.len() {
+            units[len] = match code[symbol] {
+                MorseSymbol::Dot => MORSE_DOT_MILLIS,
+                MorseSymbol::Dash => MORSE_DASH_MILLIS,
+            };
+            units[len + 1] = MORSE_DOT_MILLIS;
+            len += 2;
+            symbol += 1;
+        }
+        // The last symbol of the letter is followed by a letter gap.
+        if units[len - 1].as_ticks() < MORSE_LETTER_GAP_MILLIS.as_ticks() {
+            units[len - 1] = MORSE_LETTER_GAP_MILLIS;
+        }
+    }
+    // Separate the end of the message from its next repetition.
+    if len > 0 {
+        units[len - 1] = MORSE_WORD_GAP_MILLIS;
+    }
+
+    let mut durations = units;
+    let mut at = 0;
+    while at < M {
+        let Some(ticks) = units[at].as_ticks().checked_mul(millis_per_dot) else {
+            panic!("A Morse duration overflows");
+        };
+        durations[at] = Duration::from_ticks(ticks);
+        at += 1;
+    }
+    durations
+}
+
+// Check that `morse_durations` spells "SOS" the way `Schedule::sos_*` does: the letters of
+// `MORSE_S_MILLIS` and `MORSE_O_MILLIS`, each closed by a letter gap, and a word gap at the end.
+#[expect(
+    clippy::indexing_slicing,
+    reason = "Evaluated at compile time, so any errors will be caught at compile time."
+)]
+const _: () = {
+    const SOS: [Duration; 18] = morse_durations::<{ morse_len("SOS") }>("sos", 1);
+    let mut index = 0;
+    while index < 5 {
+        assert!(SOS[index].as_ticks() == MORSE_S_MILLIS[index].as_ticks());
+        assert!(SOS[index + 6].as_ticks() == MORSE_O_MILLIS[index].as_ticks());
+        assert!(SOS[index + 12].as_ticks() == MORSE_S_MILLIS[index].as_ticks());
+        index += 1;
+    }
+    assert!(SOS[5].as_ticks() == MORSE_LETTER_GAP_MILLIS.as_ticks());
+    assert!(SOS[11].as_ticks() == MORSE_LETTER_GAP_MILLIS.as_ticks());
+    assert!(SOS[17].as_ticks() == MORSE_WORD_GAP_MILLIS.as_ticks());
+    assert!(morse_len("E E") == 4 && morse_len("") == 0);
+};
+
 /// Morse code representation for 'S' with interleaved delays.
 pub const MORSE_S_MILLIS: [Duration; 5] =
     pad([MORSE_DOT_MILLIS, MORSE_DOT_MILLIS, MORSE_DOT_MILLIS], MORSE_DOT_MILLIS);
@@ -46,7 +456,8 @@ pub const MORSE_O_MILLIS: [Duration; 5] =
 ///
 /// # Returns
 ///
-/// An array where each input duration is followed by the padding duration.
+/// An array where each input duration but the last is followed by the padding duration, so `M`
+/// must be `2 * N - 1`. (The gap after the last one is the caller's, e.g. a letter gap.)
 #[expect(
     clippy::indexing_slicing,
     clippy::arithmetic_side_effects,
@@ -56,6 +467,8 @@ const fn pad<const N: usize, const M: usize>(
     input: [Duration; N],
     padding: Duration,
 ) -> [Duration; M] {
+    // A longer `M` would silently end in zero durations, and a shorter one drop symbols.
+    assert!(M + 1 == 2 * N, "A padded letter must hold 2 * N - 1 durations");
     let mut result = [Duration::MIN; M];
     let mut i = 0;
     while i < N {
@@ -67,3 +480,49 @@ const fn pad<const N: usize, const M: usize>(
     }
     result
 }
+
+/// Returns whether `padded` is `letter`'s code from `MORSE_ALPHABET` as `pad` lays it out: each
+/// symbol's length in dots (`MORSE_DOT_MILLIS` or `MORSE_DASH_MILLIS`), with `MORSE_DOT_MILLIS`
+/// between symbols and nothing after the last.
+#[expect(
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects,
+    reason = "Used with const, so any errors will be caught at compile time."
+)]
+const fn is_padded_letter(padded: &[Duration], letter: u8) -> bool {
+    let Some(code) = morse_for_byte(letter) else {
+        return false;
+    };
+    if code.is_empty() || padded.len() != 2 * code.len() - 1 {
+        return false;
+    }
+    let mut symbol = 0;
+    while symbol < code.len() {
+        let length = match code[symbol] {
+            MorseSymbol::Dot => MORSE_DOT_MILLIS,
+            MorseSymbol::Dash => MORSE_DASH_MILLIS,
+        };
+        if padded[symbol * 2].as_ticks() != length.as_ticks() {
+            return false;
+        }
+        if symbol + 1 < code.len()
+            && padded[symbol * 2 + 1].as_ticks() != MORSE_DOT_MILLIS.as_ticks()
+        {
+            return false;
+        }
+        symbol += 1;
+    }
+    true
+}
+
+// Check that each padded letter has the length `pad` should give it, its symbols match
+// `MORSE_ALPHABET` (which `Schedule::from_morse` decodes with), and its interior padding is
+// `MORSE_DOT_MILLIS`. There's no host test harness, so this is checked at compile time. A new
+// padded letter should be added here.
+const _: () = {
+    assert!(is_padded_letter(&MORSE_S_MILLIS, b'S'));
+    assert!(is_padded_letter(&MORSE_O_MILLIS, b'O'));
+    // A misplaced symbol or a trailing pad is caught.
+    assert!(!is_padded_letter(&MORSE_S_MILLIS, b'O'));
+    assert!(!is_padded_letter(&[MORSE_DOT_MILLIS, MORSE_DOT_MILLIS], b'E'));
+};
@@ -0,0 +1,29 @@
+use core::future::Future;
+
+use crate::error::Result;
+
+/// A state machine whose states each run until something (usually a button press) says which
+/// state comes next.
+///
+/// `LedState` is the example: its `Context` is an `LedContext` holding the LEDs, buttons and
+/// buzzer it drives. For another device (a clock, a thermostat), define an enum of states and
+/// a context with its peripherals, implement this trait, and reuse the driver loop in
+/// `main.rs`, which boils down to:
+///
+/// ```rust,ignore
+/// let mut state = MyState::default();
+/// loop {
+///     state = state.step(&mut context).await?;
+/// }
+/// ```
+pub trait StateMachine: Sized {
+    /// What the states drive and read: peripherals, settings and so on.
+    type Context;
+
+    /// Runs this state until it is time to move on, then returns the next state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state can't run, e.g. because a peripheral failed.
+    fn step(self, context: &mut Self::Context) -> impl Future<Output = Result<Self>>;
+}
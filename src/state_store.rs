@@ -0,0 +1,54 @@
+use embassy_rp::{
+    flash::{Blocking, Flash},
+    peripherals::FLASH,
+};
+
+use crate::{
+    error::Result,
+    shared_const::{FLASH_SIZE, STATE_FLASH_OFFSET, STATE_FLASH_SECTOR_SIZE},
+};
+
+/// Type alias for the RP2040's on-board flash, accessed in blocking mode.
+pub type StateFlash<'a> = Flash<'a, FLASH, Blocking, FLASH_SIZE>;
+
+/// Stores one byte of application state in a reserved flash sector, so it survives power cycles.
+///
+/// The sector is the last one in flash, which `memory.x` keeps out of the program's reach.
+pub struct StateStore<'a>(StateFlash<'a>);
+
+impl<'a> StateStore<'a> {
+    /// Creates a new `StateStore` instance.
+    #[must_use]
+    pub const fn new(flash: StateFlash<'a>) -> Self {
+        Self(flash)
+    }
+
+    /// Reads the stored byte. A sector that has never been written reads as `0xff`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flash cannot be read.
+    pub fn read(&mut self) -> Result<u8> {
+        let mut bytes = [0xff];
+        self.0.blocking_read(STATE_FLASH_OFFSET, &mut bytes)?;
+        let [byte] = bytes;
+        Ok(byte)
+    }
+
+    /// Stores `byte`, skipping the write if it is already stored.
+    ///
+    /// Erasing the sector takes tens of milliseconds, during which no task runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flash cannot be read, erased or written.
+    pub fn write(&mut self, byte: u8) -> Result<()> {
+        if self.read()? == byte {
+            return Ok(());
+        }
+        let sector_end = STATE_FLASH_OFFSET.saturating_add(STATE_FLASH_SECTOR_SIZE);
+        self.0.blocking_erase(STATE_FLASH_OFFSET, sector_end)?;
+        self.0.blocking_write(STATE_FLASH_OFFSET, &[byte])?;
+        Ok(())
+    }
+}
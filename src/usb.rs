@@ -0,0 +1,243 @@
+//! A USB serial console. The USB stack's types are not `Send`, so neither are its futures.
+#![expect(clippy::future_not_send, reason = "Safe in single-threaded, bare-metal embedded context")]
+
+use core::fmt::Write as _;
+
+use embassy_executor::{SpawnError, Spawner};
+use embassy_futures::join::join;
+use embassy_rp::{peripherals::USB, usb::Driver};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
+use embassy_time::Duration;
+use embassy_usb::{
+    class::cdc_acm::{CdcAcmClass, State},
+    Builder, UsbDevice,
+};
+use heapless::String;
+
+use crate::{
+    error::{Error, Result},
+    shared_const::{
+        FASTEST_TIME_SCALE_PERMILLE, SERIAL_COMMAND_QUEUE_LEN, SERIAL_LINE_CAPACITY,
+        SERIAL_PACKET_SIZE, UNSCALED_TIME_PERMILLE, USB_PRODUCT_ID, USB_VENDOR_ID,
+    },
+    LedState, Schedule,
+};
+
+/// Type alias for the queue of commands that a `SerialConsole` receives.
+pub type SerialCommands = Channel<CriticalSectionRawMutex, SerialCommand, SERIAL_COMMAND_QUEUE_LEN>;
+
+/// A text console over USB serial (CDC-ACM) that turns lines typed on the host into
+/// `SerialCommand`s.
+///
+/// Each line is one command, answered with `ok` or with `error:` and the reason it was rejected:
+///
+/// ```text
+/// state <name>                        switch to a state: fast-alternate, fast-together,
+///                                     slow-alternate, speed-cycle, breathing, sos, on, off,
+///                                     morse or dual-morse
+/// speed <factor>                      run every pattern <factor> times faster (1 is normal,
+///                                     at most as fast as the knob goes)
+/// schedule <delay> <on> <off> [...]   show a custom pattern on both LEDs until a button is
+///                                     pressed; all durations are in milliseconds
+/// ```
+///
+/// Words are separated by spaces and are case-insensitive. A line that doesn't match the grammar
+/// (or is longer than `SERIAL_LINE_CAPACITY` bytes) is rejected with `Error::CommandParse`.
+pub struct SerialConsole {
+    commands: &'static SerialCommands,
+}
+
+/// A command typed on the `SerialConsole`.
+#[expect(
+    clippy::large_enum_variant,
+    reason = "There is no heap to box into, and a queue slot must fit a schedule anyway."
+)]
+#[derive(Debug)]
+pub enum SerialCommand {
+    /// Switch the state machine to this state.
+    State(LedState),
+    /// Set the LEDs' time scale, in per mille (see `Led::set_time_scale`).
+    TimeScale(u32),
+    /// Show this schedule on both LEDs until a button is pressed.
+    Schedule(Schedule),
+}
+
+impl SerialCommand {
+    /// Parses one line of the console grammar (see `SerialConsole`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CommandParse` if `line` doesn't match the grammar (including a `speed`
+    /// beyond `FASTEST_TIME_SCALE_PERMILLE`, which would round the LEDs' delays down to
+    /// nothing), and an error from `ScheduleBuilder` if a `schedule` has too many (or an odd
+    /// number of) segments.
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut words = line.split_ascii_whitespace();
+        let keyword = words.next().ok_or(Error::CommandParse)?;
+        let command = if keyword.eq_ignore_ascii_case("state") {
+            let name = words.next().ok_or(Error::CommandParse)?;
+            Self::State(parse_state(name)?)
+        } else if keyword.eq_ignore_ascii_case("speed") {
+            let factor = parse_number(words.next())?;
+            let permille = u64::from(UNSCALED_TIME_PERMILLE)
+                .checked_div(factor)
+                .and_then(|permille| u32::try_from(permille).ok())
+                .filter(|&permille| permille >= FASTEST_TIME_SCALE_PERMILLE)
+                .ok_or(Error::CommandParse)?;
+            Self::TimeScale(permille)
+        } else if keyword.eq_ignore_ascii_case("schedule") {
+            let initial_delay = Duration::from_millis(parse_number(words.next())?);
+            let mut builder = Schedule::builder().initial_delay(initial_delay);
+            for (index, word) in words.by_ref().enumerate() {
+                let duration = Duration::from_millis(parse_number(Some(word))?);
+                builder = if index & 1 == 0 {
+                    builder.on(duration)?
+                } else {
+                    builder.off(duration)?
+                };
+            }
+            Self::Schedule(builder.build()?)
+        } else {
+            return Err(Error::CommandParse);
+        };
+        // `schedule` consumes every word, so anything left is extra.
+        if words.next().is_some() {
+            return Err(Error::CommandParse);
+        }
+        Ok(command)
+    }
+}
+
+impl SerialConsole {
+    /// Creates a new `SerialConsole`, which entails starting an Embassy task that runs the USB
+    /// device on `driver`.
+    ///
+    /// # Arguments
+    ///
+    /// * `driver` - The RP2040's USB driver.
+    /// * `commands` - The static queue that parsed commands are put on. It is created with the
+    ///   `SerialConsole::commands()` method.
+    /// * `spawner` - The spawner that will spawn the task.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpawnError` if the task cannot be spawned.
+    pub fn new(
+        driver: Driver<'static, USB>,
+        commands: &'static SerialCommands,
+        spawner: Spawner,
+    ) -> Result<Self, SpawnError> {
+        let mut config = embassy_usb::Config::new(USB_VENDOR_ID, USB_PRODUCT_ID);
+        config.manufacturer = Some("dua_blinka");
+        config.product = Some("dua_blinka console");
+        config.max_power = 100;
+        config.max_packet_size_0 = 64;
+
+        // The USB stack keeps these buffers for as long as it runs, i.e. forever. `Driver<USB>`
+        // can only be created once, so neither can they.
+        let (Some(config_descriptor), Some(bos_descriptor), Some(control_buf), Some(state)) = (
+            cortex_m::singleton!(: [u8; 256] = [0; 256]),
+            cortex_m::singleton!(: [u8; 256] = [0; 256]),
+            cortex_m::singleton!(: [u8; 64] = [0; 64]),
+            cortex_m::singleton!(: State<'static> = State::new()),
+        ) else {
+            defmt::panic!("The USB serial console was already created");
+        };
+        let mut builder =
+            Builder::new(driver, config, config_descriptor, bos_descriptor, &mut [], control_buf);
+        let class = CdcAcmClass::new(&mut builder, state, SERIAL_PACKET_SIZE);
+        let device = builder.build();
+
+        spawner.spawn(device_task(device, class, commands))?;
+        Ok(Self { commands })
+    }
+
+    /// Creates a new `SerialCommands` queue.
+    ///
+    /// The queue should be assigned to a static variable and passed to `SerialConsole::new()`.
+    #[must_use]
+    pub const fn commands() -> SerialCommands {
+        Channel::new()
+    }
+
+    /// Waits for the next command typed on the console.
+    pub async fn receive(&self) -> SerialCommand {
+        self.commands.receive().await
+    }
+}
+
+#[embassy_executor::task]
+async fn device_task(
+    mut device: UsbDevice<'static, Driver<'static, USB>>,
+    class: CdcAcmClass<'static, Driver<'static, USB>>,
+    commands: &'static SerialCommands,
+) -> ! {
+    join(device.run(), console_loop(class, commands)).await.0
+}
+
+/// Reads lines from `class`, forever, putting each valid command on `commands`.
+async fn console_loop(
+    mut class: CdcAcmClass<'static, Driver<'static, USB>>,
+    commands: &'static SerialCommands,
+) -> ! {
+    let mut packet = [0; SERIAL_PACKET_SIZE as usize];
+    let mut line: String<SERIAL_LINE_CAPACITY> = String::new();
+    let mut overflowed = false;
+    loop {
+        class.wait_connection().await;
+        // A read error means the host disconnected, so wait for it to come back.
+        while let Ok(len) = class.read_packet(&mut packet).await {
+            for &byte in packet.get(..len).unwrap_or_default() {
+                if byte != b'\r' && byte != b'\n' {
+                    overflowed |= line.push(char::from(byte)).is_err();
+                    continue;
+                }
+                if line.is_empty() && !overflowed {
+                    continue;
+                }
+                let reply = if overflowed {
+                    Err(Error::CommandParse)
+                } else {
+                    SerialCommand::parse(&line)
+                };
+                let mut text: String<SERIAL_LINE_CAPACITY> = String::new();
+                // A reply too long for `text` is truncated, which is fine for a console.
+                let _ = match reply {
+                    Ok(command) => {
+                        commands.send(command).await;
+                        write!(text, "ok\r\n")
+                    },
+                    Err(err) => write!(text, "error: {err}\r\n"),
+                };
+                // The reply is best effort; a disconnect is noticed by the next read.
+                let _ = class.write_packet(text.as_bytes()).await;
+                line.clear();
+                overflowed = false;
+            }
+        }
+    }
+}
+
+fn parse_state(name: &str) -> Result<LedState> {
+    const NAMES: [(&str, LedState); 10] = [
+        ("fast-alternate", LedState::FastAlternate),
+        ("fast-together", LedState::FastTogether),
+        ("slow-alternate", LedState::SlowAlternate),
+        ("speed-cycle", LedState::SpeedCycle { level: 0 }),
+        ("breathing", LedState::Breathing),
+        ("sos", LedState::Sos),
+        ("on", LedState::AlwaysOn),
+        ("off", LedState::AlwaysOff),
+        ("morse", LedState::AllMorse),
+        ("dual-morse", LedState::DualMorse),
+    ];
+    NAMES
+        .iter()
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+        .map(|&(_, state)| state)
+        .ok_or(Error::CommandParse)
+}
+
+fn parse_number(word: Option<&str>) -> Result<u64> {
+    word.and_then(|digits| digits.parse().ok()).ok_or(Error::CommandParse)
+}
@@ -0,0 +1,64 @@
+use core::{future::Future, pin::pin};
+
+use embassy_futures::select::{select, Either};
+use embassy_rp::peripherals::WATCHDOG;
+use embassy_time::{Duration, Timer};
+
+/// Resets the device if it isn't fed in time, so that a stalled executor doesn't hang the device
+/// silently.
+///
+/// The main loop feeds it at the top of each pass. Most states, though, await a button press
+/// indefinitely (e.g. `AlwaysOn` or `Sos` until someone comes along), so a pass can legitimately
+/// take hours. Wrap such waits in `feed_while()`, which keeps feeding every half timeout for as
+/// long as the wait lasts. The feeding runs on the same executor as the wait, so a task that
+/// blocks that executor (a busy loop, a blocking call that never returns) still starves it and
+/// resets the device. A future that merely never completes does not: it is indistinguishable from
+/// a long wait for a button.
+///
+/// The watchdog's tick stops while the chip is `DORMANT` (see `Power`), so sleeping doesn't reset
+/// it either.
+pub struct Watchdog {
+    watchdog: embassy_rp::watchdog::Watchdog,
+    feed_interval: Duration,
+}
+
+impl Watchdog {
+    /// Creates a new, stopped `Watchdog`. It is paused while a debugger is attached.
+    #[must_use]
+    pub fn new(peripheral: WATCHDOG) -> Self {
+        let mut watchdog = embassy_rp::watchdog::Watchdog::new(peripheral);
+        watchdog.pause_on_debug(true);
+        Self {
+            watchdog,
+            feed_interval: Duration::MAX,
+        }
+    }
+
+    /// Starts the watchdog, which will reset the device unless it is fed at least every `timeout`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` exceeds the hardware's limit of about 8.3 seconds.
+    pub fn start(&mut self, timeout: Duration) {
+        self.feed_interval = Duration::from_ticks(timeout.as_ticks().checked_div(2).unwrap_or(0));
+        self.watchdog.start(timeout);
+    }
+
+    /// Feeds the watchdog, putting off the reset for another timeout.
+    pub fn feed(&mut self) {
+        self.watchdog.feed();
+    }
+
+    /// Runs `future` to completion, feeding the watchdog every half timeout until it completes.
+    pub async fn feed_while<F: Future>(&mut self, future: F) -> F::Output {
+        let mut pinned = pin!(future);
+        loop {
+            self.feed();
+            if let Either::First(output) =
+                select(&mut pinned, Timer::after(self.feed_interval)).await
+            {
+                return output;
+            }
+        }
+    }
+}